@@ -0,0 +1,402 @@
+//! Iterators over arrangements of a slice's elements.
+//!
+//! Each iterator yields a fresh `Vec` per item, and also has a lending
+//! `next_ref` that refills one buffer instead, for hot loops that only
+//! need to look at each arrangement.
+
+/// Implements the shared parts of an arrangement iterator, given a type with
+/// `elements`, `buffer`, `pending` and `remaining` fields, a `selected`
+/// method returning the positions of the current item, and an `advance`
+/// method that moves on to the next item and says whether there is one.
+macro_rules! arrangements {
+    ($name:ident) => {
+        impl<'a, T: Clone> $name<'a, T> {
+            /// Like [`Iterator::next`], but reuses an internal buffer.
+            pub fn next_ref(&mut self) -> Option<&[T]> {
+                if !self.pending {
+                    return None;
+                }
+                let mut buffer = std::mem::take(&mut self.buffer);
+                buffer.clear();
+                buffer.extend(self.selected().iter().map(|&i| self.elements[i].clone()));
+                self.buffer = buffer;
+                self.pending = self.advance();
+                self.remaining = self.remaining.map(|n| n.saturating_sub(1));
+                Some(&self.buffer)
+            }
+        }
+
+        impl<'a, T: Clone> Iterator for $name<'a, T> {
+            type Item = Vec<T>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.next_ref().map(<[T]>::to_vec)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                match self.remaining {
+                    Some(n) => (n, Some(n)),
+                    None => (usize::MAX, None),
+                }
+            }
+        }
+    };
+}
+
+/// Every sequence of `m` elements, each picked from `elements` with
+/// repetition, in lexicographic order of positions.
+pub struct CartesianPower<'a, T> {
+    elements: &'a [T],
+    indices: Vec<usize>,
+    buffer: Vec<T>,
+    pending: bool,
+    remaining: Option<usize>,
+}
+
+pub fn cartesian_power<T>(elements: &[T], m: usize) -> CartesianPower<'_, T> {
+    let n = elements.len();
+    CartesianPower {
+        elements,
+        indices: vec![0; m],
+        buffer: Vec::with_capacity(m),
+        // No elements can only fill a sequence of length zero.
+        pending: n > 0 || m == 0,
+        remaining: u32::try_from(m).ok().and_then(|m| n.checked_pow(m)),
+    }
+}
+
+impl<T> CartesianPower<'_, T> {
+    fn selected(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Increments the indices like a base-`n` counter.
+    fn advance(&mut self) -> bool {
+        let n = self.elements.len();
+        for i in self.indices.iter_mut().rev() {
+            *i += 1;
+            if *i < n {
+                return true;
+            }
+            *i = 0;
+        }
+        false
+    }
+}
+
+arrangements!(CartesianPower);
+
+/// Every ordering of `k` distinct positions of `elements`, in
+/// lexicographic order of positions.
+pub struct Permutations<'a, T> {
+    elements: &'a [T],
+    indices: Vec<usize>,
+    cycles: Vec<usize>,
+    buffer: Vec<T>,
+    pending: bool,
+    remaining: Option<usize>,
+}
+
+pub fn permutations<T>(elements: &[T], k: usize) -> Permutations<'_, T> {
+    let n = elements.len();
+    Permutations {
+        elements,
+        indices: (0..n).collect(),
+        cycles: (n.saturating_sub(k) + 1..=n).rev().collect(),
+        buffer: Vec::with_capacity(k),
+        pending: k <= n,
+        remaining: if k <= n {
+            (n - k + 1..=n).try_fold(1usize, |acc, i| acc.checked_mul(i))
+        } else {
+            Some(0)
+        },
+    }
+}
+
+impl<T> Permutations<'_, T> {
+    fn selected(&self) -> &[usize] {
+        &self.indices[..self.cycles.len()]
+    }
+
+    /// The rotating-cycles scheme from Python's `itertools.permutations`:
+    /// only the first `k` indices are read, the rest hold what's unused.
+    fn advance(&mut self) -> bool {
+        let n = self.indices.len();
+        for i in (0..self.cycles.len()).rev() {
+            self.cycles[i] -= 1;
+            if self.cycles[i] == 0 {
+                self.indices[i..].rotate_left(1);
+                self.cycles[i] = n - i;
+            } else {
+                let j = n - self.cycles[i];
+                self.indices.swap(i, j);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+arrangements!(Permutations);
+
+/// Every choice of `k` distinct positions of `elements`, ignoring order,
+/// in lexicographic order of positions.
+pub struct Combinations<'a, T> {
+    elements: &'a [T],
+    indices: Vec<usize>,
+    buffer: Vec<T>,
+    pending: bool,
+    remaining: Option<usize>,
+}
+
+pub fn combinations<T>(elements: &[T], k: usize) -> Combinations<'_, T> {
+    let n = elements.len();
+    Combinations {
+        elements,
+        indices: (0..k).collect(),
+        buffer: Vec::with_capacity(k),
+        pending: k <= n,
+        remaining: if k <= n { binomial(n, k) } else { Some(0) },
+    }
+}
+
+impl<T> Combinations<'_, T> {
+    fn selected(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Bumps the rightmost index that still has room, then packs the ones
+    /// after it right behind it.
+    fn advance(&mut self) -> bool {
+        let n = self.elements.len();
+        let k = self.indices.len();
+        let Some(i) = (0..k).rev().find(|&i| self.indices[i] < n - k + i) else {
+            return false;
+        };
+        self.indices[i] += 1;
+        for j in i + 1..k {
+            self.indices[j] = self.indices[j - 1] + 1;
+        }
+        true
+    }
+}
+
+arrangements!(Combinations);
+
+/// Every choice of `k` positions of `elements` where positions may repeat,
+/// ignoring order, as non-decreasing position sequences.
+pub struct CombinationsWithReplacement<'a, T> {
+    elements: &'a [T],
+    indices: Vec<usize>,
+    buffer: Vec<T>,
+    pending: bool,
+    remaining: Option<usize>,
+}
+
+pub fn combinations_with_replacement<T>(
+    elements: &[T],
+    k: usize,
+) -> CombinationsWithReplacement<'_, T> {
+    let n = elements.len();
+    CombinationsWithReplacement {
+        elements,
+        indices: vec![0; k],
+        buffer: Vec::with_capacity(k),
+        pending: n > 0 || k == 0,
+        remaining: if n > 0 {
+            binomial(n + k - 1, k)
+        } else {
+            Some(usize::from(k == 0))
+        },
+    }
+}
+
+impl<T> CombinationsWithReplacement<'_, T> {
+    fn selected(&self) -> &[usize] {
+        &self.indices
+    }
+
+    fn advance(&mut self) -> bool {
+        let n = self.elements.len();
+        let Some(i) = self.indices.iter().rposition(|&i| i + 1 < n) else {
+            return false;
+        };
+        let next = self.indices[i] + 1;
+        self.indices[i..].fill(next);
+        true
+    }
+}
+
+arrangements!(CombinationsWithReplacement);
+
+/// `n` choose `k`, or `None` if it doesn't fit in a `usize`.
+fn binomial(n: usize, k: usize) -> Option<usize> {
+    let k = k.min(n - k);
+    let mut result = 1u128;
+    for i in 0..k {
+        // Exact: the running product of `i + 1` consecutive numbers is
+        // always divisible by `(i + 1)!`.
+        result = result.checked_mul((n - i) as u128)? / (i as u128 + 1);
+    }
+    usize::try_from(result).ok()
+}
+
+/// Every unordered pair of elements at distinct positions.
+pub struct Pairs<'a, T> {
+    elements: &'a [T],
+    i: usize,
+    j: usize,
+}
+
+pub fn pairs<T>(elements: &[T]) -> Pairs<'_, T> {
+    Pairs {
+        elements,
+        i: 0,
+        j: 1,
+    }
+}
+
+impl<'a, T> Iterator for Pairs<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.j >= self.elements.len() {
+            self.i += 1;
+            self.j = self.i + 1;
+            if self.j >= self.elements.len() {
+                return None;
+            }
+        }
+        let pair = (&self.elements[self.i], &self.elements[self.j]);
+        self.j += 1;
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.elements.len();
+        let rest_of_row = n.saturating_sub(self.j);
+        let later_rows = n.saturating_sub(self.i + 1);
+        let remaining = rest_of_row + later_rows * later_rows.saturating_sub(1) / 2;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Pairs<'_, T> {}
+
+/// Rearranges `elements` into the next greater permutation in lexicographic
+/// order, returning `false` (and sorting them) after the greatest one.
+pub fn next_permutation<T: Ord>(elements: &mut [T]) -> bool {
+    let Some(i) = elements.windows(2).rposition(|w| w[0] < w[1]) else {
+        elements.reverse();
+        return false;
+    };
+    let j = elements.iter().rposition(|e| *e > elements[i]).unwrap();
+    elements.swap(i, j);
+    elements[i + 1..].reverse();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_size<I: Iterator>(iter: I) -> Vec<I::Item> {
+        let (lower, upper) = iter.size_hint();
+        let items = iter.collect::<Vec<_>>();
+        assert_eq!((lower, upper), (items.len(), Some(items.len())));
+        items
+    }
+
+    #[test]
+    fn test_cartesian_power() {
+        let items = check_size(cartesian_power(&['a', 'b'], 2));
+        assert_eq!(
+            items,
+            [['a', 'a'], ['a', 'b'], ['b', 'a'], ['b', 'b']].map(Vec::from)
+        );
+        assert_eq!(check_size(cartesian_power(&[1, 2, 3], 0)), [vec![]]);
+        assert!(check_size(cartesian_power::<i32>(&[], 2)).is_empty());
+        assert_eq!(cartesian_power(&[0; 3], 4).count(), 81);
+    }
+
+    #[test]
+    fn test_permutations() {
+        let items = check_size(permutations(&[1, 2, 3], 2));
+        assert_eq!(
+            items,
+            [[1, 2], [1, 3], [2, 1], [2, 3], [3, 1], [3, 2]].map(Vec::from)
+        );
+        assert_eq!(check_size(permutations(&[1, 2, 3, 4], 4)).len(), 24);
+        assert_eq!(check_size(permutations(&[1, 2], 0)), [vec![]]);
+        assert!(check_size(permutations(&[1, 2], 3)).is_empty());
+    }
+
+    #[test]
+    fn test_combinations() {
+        let items = check_size(combinations(&[1, 2, 3, 4], 2));
+        assert_eq!(
+            items,
+            [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]].map(Vec::from)
+        );
+        assert_eq!(check_size(combinations(&[1, 2, 3], 3)), [vec![1, 2, 3]]);
+        assert_eq!(check_size(combinations(&[1, 2], 0)), [vec![]]);
+        assert!(check_size(combinations(&[1, 2], 3)).is_empty());
+    }
+
+    #[test]
+    fn test_combinations_with_replacement() {
+        let items = check_size(combinations_with_replacement(&[1, 2, 3], 2));
+        assert_eq!(
+            items,
+            [[1, 1], [1, 2], [1, 3], [2, 2], [2, 3], [3, 3]].map(Vec::from)
+        );
+        assert_eq!(check_size(combinations_with_replacement(&[1], 3)).len(), 1);
+        assert_eq!(
+            check_size(combinations_with_replacement::<i32>(&[], 0)),
+            [vec![]]
+        );
+        assert!(check_size(combinations_with_replacement::<i32>(&[], 2)).is_empty());
+    }
+
+    #[test]
+    fn test_pairs() {
+        let items = check_size(pairs(&[1, 2, 3]));
+        assert_eq!(items, [(&1, &2), (&1, &3), (&2, &3)]);
+        assert!(check_size(pairs(&[1])).is_empty());
+        assert!(check_size(pairs::<i32>(&[])).is_empty());
+        let mut iter = pairs(&[1, 2, 3, 4]);
+        iter.next();
+        assert_eq!(iter.len(), 5);
+    }
+
+    #[test]
+    fn test_next_ref() {
+        let mut iter = cartesian_power(&[0, 1], 3);
+        let mut sums = Vec::new();
+        while let Some(bits) = iter.next_ref() {
+            sums.push(bits.iter().sum::<i32>());
+        }
+        assert_eq!(sums, [0, 1, 1, 2, 1, 2, 2, 3]);
+        let mut iter = permutations(&['x', 'y'], 2);
+        assert_eq!(iter.next_ref(), Some(&['x', 'y'][..]));
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+    }
+
+    #[test]
+    fn test_next_permutation() {
+        let mut items = [1, 2, 2];
+        let mut seen = vec![items];
+        while next_permutation(&mut items) {
+            seen.push(items);
+        }
+        assert_eq!(seen, [[1, 2, 2], [2, 1, 2], [2, 2, 1]]);
+        assert_eq!(items, [1, 2, 2]);
+    }
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(binomial(5, 2), Some(10));
+        assert_eq!(binomial(60, 30), Some(118264581564861424));
+        assert_eq!(binomial(200, 100), None);
+    }
+}
@@ -0,0 +1,364 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A signed position, with rows growing downwards like in puzzle inputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+/// The difference between two points.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(row: i64, col: i64) -> Self {
+        Self { row, col }
+    }
+
+    pub fn manhattan(&self, other: Self) -> i64 {
+        (*self - other).manhattan()
+    }
+
+    pub fn chebyshev(&self, other: Self) -> i64 {
+        (*self - other).chebyshev()
+    }
+
+    /// The `(row, col)` grid position, if neither coordinate is negative.
+    pub fn to_pos(&self) -> Option<(usize, usize)> {
+        Some((
+            usize::try_from(self.row).ok()?,
+            usize::try_from(self.col).ok()?,
+        ))
+    }
+
+    pub fn from_pos((row, col): (usize, usize)) -> Self {
+        Self::new(row as i64, col as i64)
+    }
+
+    /// The index into row-major storage of the given width, if the point
+    /// lies within that width.
+    pub fn to_index(&self, width: usize) -> Option<usize> {
+        let (row, col) = self.to_pos()?;
+        (col < width).then(|| row * width + col)
+    }
+
+    pub fn from_index(index: usize, width: usize) -> Self {
+        Self::from_pos((index / width, index % width))
+    }
+
+    pub fn neighbors4(&self) -> impl Iterator<Item = Self> {
+        let point = *self;
+        Direction::ALL.into_iter().map(move |d| point + d.offset())
+    }
+
+    pub fn neighbors8(&self) -> impl Iterator<Item = Self> {
+        let point = *self;
+        Direction8::ALL.into_iter().map(move |d| point + d.offset())
+    }
+}
+
+impl Vector {
+    pub const fn new(row: i64, col: i64) -> Self {
+        Self { row, col }
+    }
+
+    pub fn manhattan(&self) -> i64 {
+        self.row.abs() + self.col.abs()
+    }
+
+    pub fn chebyshev(&self) -> i64 {
+        self.row.abs().max(self.col.abs())
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from(pos: (usize, usize)) -> Self {
+        Self::from_pos(pos)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Self;
+
+    fn add(self, other: Vector) -> Self {
+        Self::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, other: Vector) {
+        *self = *self + other;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Self;
+
+    fn sub(self, other: Vector) -> Self {
+        Self::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, other: Vector) {
+        *self = *self - other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Self) -> Vector {
+        Vector::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Add for Vector {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Self;
+
+    fn mul(self, factor: i64) -> Self {
+        Self::new(self.row * factor, self.col * factor)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.row, -self.col)
+    }
+}
+
+/// The four cardinal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise from up.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// Parses the `^`, `>`, `v` and `<` arrows used in puzzle maps.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Self::Up),
+            '>' => Some(Self::Right),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub fn turn_left(&self) -> Self {
+        self.opposite().turn_right()
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    pub fn offset(&self) -> Vector {
+        match self {
+            Self::Up => Vector::new(-1, 0),
+            Self::Right => Vector::new(0, 1),
+            Self::Down => Vector::new(1, 0),
+            Self::Left => Vector::new(0, -1),
+        }
+    }
+}
+
+/// The four cardinal directions plus the four ordinal (diagonal) ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// Clockwise from up.
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// The four diagonal directions, clockwise from up-right.
+    pub const ORDINAL: [Self; 4] = [Self::UpRight, Self::DownRight, Self::DownLeft, Self::UpLeft];
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|d| d == self).unwrap()
+    }
+
+    /// Rotates clockwise by `steps` eighths of a turn.
+    pub fn rotate(&self, steps: i32) -> Self {
+        Self::ALL[(self.index() as i32 + steps).rem_euclid(8) as usize]
+    }
+
+    pub fn turn_right(&self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(&self) -> Self {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn offset(&self) -> Vector {
+        match self {
+            Self::Up => Vector::new(-1, 0),
+            Self::UpRight => Vector::new(-1, 1),
+            Self::Right => Vector::new(0, 1),
+            Self::DownRight => Vector::new(1, 1),
+            Self::Down => Vector::new(1, 0),
+            Self::DownLeft => Vector::new(1, -1),
+            Self::Left => Vector::new(0, -1),
+            Self::UpLeft => Vector::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Self::Up,
+            Direction::Right => Self::Right,
+            Direction::Down => Self::Down,
+            Direction::Left => Self::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let result = Point::new(1, 2) + Vector::new(3, 4);
+        assert_eq!(result, Point::new(4, 6));
+    }
+
+    #[test]
+    fn test_sub() {
+        let result = Point::new(3, 4) - Point::new(1, 2);
+        assert_eq!(result, Vector::new(2, 2));
+        assert_eq!(Point::new(1, 1) - Vector::new(2, 0), Point::new(-1, 1));
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(Vector::new(1, -2) * 3, Vector::new(3, -6));
+        assert_eq!(-Vector::new(1, -2), Vector::new(-1, 2));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, 1);
+        let b = Point::new(4, -1);
+        assert_eq!(a.manhattan(b), 5);
+        assert_eq!(a.chebyshev(b), 3);
+    }
+
+    #[test]
+    fn test_grid_conversions() {
+        assert_eq!(Point::new(2, 3).to_pos(), Some((2, 3)));
+        assert_eq!(Point::new(-1, 3).to_pos(), None);
+        assert_eq!(Point::new(2, 3).to_index(5), Some(13));
+        assert_eq!(Point::new(2, 5).to_index(5), None);
+        assert_eq!(Point::from_index(13, 5), Point::new(2, 3));
+        assert_eq!(Point::from((2, 3)), Point::new(2, 3));
+    }
+
+    #[test]
+    fn test_direction_rotation() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        for d in Direction::ALL {
+            assert_eq!(d.offset() + d.opposite().offset(), Vector::default());
+        }
+    }
+
+    #[test]
+    fn test_direction8_rotation() {
+        assert_eq!(Direction8::Up.rotate(1), Direction8::UpRight);
+        assert_eq!(Direction8::Up.rotate(-1), Direction8::UpLeft);
+        assert_eq!(Direction8::UpLeft.turn_right(), Direction8::UpRight);
+        assert_eq!(Direction8::DownLeft.opposite(), Direction8::UpRight);
+        assert!(Direction8::ORDINAL.iter().all(|d| d.is_diagonal()));
+        assert_eq!(Direction8::from(Direction::Down), Direction8::Down);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let point = Point::new(0, 0);
+        assert_eq!(point.neighbors4().count(), 4);
+        assert!(point.neighbors8().all(|p| point.chebyshev(p) == 1));
+    }
+
+    #[test]
+    fn test_from_arrow() {
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::Down));
+        assert_eq!(Direction::from_arrow('.'), None);
+    }
+}
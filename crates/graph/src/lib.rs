@@ -0,0 +1,349 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+/// A directed graph over nodes of any hashable type. Nodes keep the order
+/// they were added in, which is also how ties are broken when sorting.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+/// Nodes that depend on each other in a loop, listed in edge order; the
+/// last node has an edge back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: fmt::Display> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle: ")?;
+        for node in &self.0 {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: fmt::Debug + fmt::Display> std::error::Error for Cycle<N> {}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            successors: Vec::new(),
+            predecessors: Vec::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `node` if it isn't already present and returns its index.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        let i = self.nodes.len();
+        self.index.insert(node.clone(), i);
+        self.nodes.push(node);
+        self.successors.push(Vec::new());
+        self.predecessors.push(Vec::new());
+        i
+    }
+
+    /// Adds an edge meaning `from` must come before `to`, adding either
+    /// node as needed. Repeated edges are ignored.
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        if !self.successors[from].contains(&to) {
+            self.successors[from].push(to);
+            self.predecessors[to].push(from);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.successors.iter().map(Vec::len).sum()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        match (self.index.get(from), self.index.get(to)) {
+            (Some(&from), Some(&to)) => self.successors[from].contains(&to),
+            _ => false,
+        }
+    }
+
+    /// Nodes in the order they were added.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        self.successors
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, tos)| {
+                tos.iter()
+                    .map(move |&to| (&self.nodes[from], &self.nodes[to]))
+            })
+    }
+
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.neighbors(node, &self.successors)
+    }
+
+    pub fn predecessors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.neighbors(node, &self.predecessors)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        node: &N,
+        adjacency: &'a [Vec<usize>],
+    ) -> impl Iterator<Item = &'a N> {
+        let list = self.index.get(node).map_or(&[][..], |&i| &adjacency[i]);
+        list.iter().map(|&i| &self.nodes[i])
+    }
+
+    /// The graph restricted to `nodes`, keeping only edges between them.
+    /// Nodes missing from this graph are included without edges.
+    pub fn induced_subgraph(&self, nodes: impl IntoIterator<Item = N>) -> Self {
+        let mut subgraph = Self::new();
+        for node in nodes {
+            subgraph.add_node(node);
+        }
+        for (from, to) in self.edges() {
+            if subgraph.contains(from) && subgraph.contains(to) {
+                subgraph.add_edge(from.clone(), to.clone());
+            }
+        }
+        subgraph
+    }
+
+    /// Orders the nodes so every edge points forward, breaking ties by
+    /// insertion order.
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        self.sort_indices(|&i| i)
+            .map(|order| order.into_iter().map(|i| self.nodes[i].clone()).collect())
+    }
+
+    /// Like [`Graph::topological_sort`], but whenever several nodes are
+    /// ready the one with the smallest key goes first.
+    pub fn topological_sort_by_key<K: Ord>(
+        &self,
+        mut key: impl FnMut(&N) -> K,
+    ) -> Result<Vec<N>, Cycle<N>> {
+        let keys = self.nodes.iter().map(&mut key).collect::<Vec<_>>();
+        self.sort_indices(|&i| (&keys[i], i))
+            .map(|order| order.into_iter().map(|i| self.nodes[i].clone()).collect())
+    }
+
+    /// Kahn's algorithm over node indices, taking the ready node with the
+    /// smallest key first.
+    fn sort_indices<K: Ord>(&self, key: impl Fn(&usize) -> K) -> Result<Vec<usize>, Cycle<N>> {
+        let mut indegree = self.predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut ready = (0..self.len())
+            .filter(|&i| indegree[i] == 0)
+            .map(|i| Reverse((key(&i), i)))
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse((_, i))) = ready.pop() {
+            order.push(i);
+            for &next in &self.successors[i] {
+                indegree[next] -= 1;
+                if indegree[next] == 0 {
+                    ready.push(Reverse((key(&next), next)));
+                }
+            }
+        }
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&indegree))
+        }
+    }
+
+    /// Every node Kahn's algorithm couldn't place still has a predecessor
+    /// it couldn't place either, so walking predecessors must loop.
+    fn find_cycle(&self, indegree: &[usize]) -> Cycle<N> {
+        let start = (0..self.len()).find(|&i| indegree[i] > 0).unwrap();
+        let mut seen = vec![None; self.len()];
+        let mut path = Vec::new();
+        let mut node = start;
+        while seen[node].is_none() {
+            seen[node] = Some(path.len());
+            path.push(node);
+            node = *self.predecessors[node]
+                .iter()
+                .find(|&&p| indegree[p] > 0)
+                .unwrap();
+        }
+        let mut cycle = path.split_off(seen[node].unwrap());
+        cycle.reverse();
+        // Start from the earliest added node so the same cycle always reads the same.
+        let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+        cycle.rotate_left(first);
+        Cycle(cycle.into_iter().map(|i| self.nodes[i].clone()).collect())
+    }
+
+    /// Every node reachable from `node` by following edges forward.
+    pub fn descendants(&self, node: &N) -> Vec<&N> {
+        self.reach(node, &self.successors)
+    }
+
+    /// Every node that can reach `node`.
+    pub fn ancestors(&self, node: &N) -> Vec<&N> {
+        self.reach(node, &self.predecessors)
+    }
+
+    fn reach(&self, node: &N, adjacency: &[Vec<usize>]) -> Vec<&N> {
+        let Some(&start) = self.index.get(node) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+        let mut found = Vec::new();
+        while let Some(i) = stack.pop() {
+            for &next in &adjacency[i] {
+                if !seen[next] {
+                    seen[next] = true;
+                    found.push(&self.nodes[next]);
+                    stack.push(next);
+                }
+            }
+        }
+        found
+    }
+
+    /// The smallest graph with the same reachability: an edge is dropped
+    /// when its target can also be reached through another path.
+    pub fn transitive_reduction(&self) -> Result<Self, Cycle<N>> {
+        let order = self.sort_indices(|&i| i)?;
+        let mut position = vec![0; self.len()];
+        for (p, &i) in order.iter().enumerate() {
+            position[i] = p;
+        }
+        let mut reachable = vec![vec![false; self.len()]; self.len()];
+        let mut reduced = Self::new();
+        for node in &self.nodes {
+            reduced.add_node(node.clone());
+        }
+        for &from in order.iter().rev() {
+            let mut targets = self.successors[from].clone();
+            targets.sort_by_key(|&to| position[to]);
+            for to in targets {
+                if !reachable[from][to] {
+                    reduced.add_edge(self.nodes[from].clone(), self.nodes[to].clone());
+                    reachable[from][to] = true;
+                    let through = reachable[to].clone();
+                    for (reach, through) in reachable[from].iter_mut().zip(through) {
+                        *reach |= through;
+                    }
+                }
+            }
+        }
+        Ok(reduced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_edges(edges: &[(i32, i32)]) -> Graph<i32> {
+        edges.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = from_edges(&[(97, 75), (75, 47), (97, 47), (47, 61), (75, 61)]);
+        assert_eq!(graph.topological_sort(), Ok(vec![97, 75, 47, 61]));
+    }
+
+    #[test]
+    fn test_topological_sort_by_key() {
+        let mut graph = from_edges(&[(3, 1), (2, 1)]);
+        graph.add_node(0);
+        assert_eq!(graph.topological_sort(), Ok(vec![3, 2, 1, 0]));
+        assert_eq!(graph.topological_sort_by_key(|&n| n), Ok(vec![0, 2, 3, 1]));
+        assert_eq!(
+            graph.topological_sort_by_key(|&n| Reverse(n)),
+            Ok(vec![3, 2, 1, 0])
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let graph = from_edges(&[(0, 1), (1, 2), (2, 3), (3, 1)]);
+        let cycle = graph.topological_sort().unwrap_err();
+        assert_eq!(cycle, Cycle(vec![1, 2, 3]));
+        assert_eq!(cycle.to_string(), "cycle: 1 -> 2 -> 3 -> 1");
+    }
+
+    #[test]
+    fn test_reach() {
+        let graph = from_edges(&[(1, 2), (2, 3), (1, 3), (4, 3)]);
+        let mut descendants = graph.descendants(&1);
+        descendants.sort();
+        assert_eq!(descendants, [&2, &3]);
+        let mut ancestors = graph.ancestors(&3);
+        ancestors.sort();
+        assert_eq!(ancestors, [&1, &2, &4]);
+        assert!(graph.descendants(&5).is_empty());
+    }
+
+    #[test]
+    fn test_induced_subgraph() {
+        let graph = from_edges(&[(1, 2), (2, 3), (1, 3), (3, 4)]);
+        let subgraph = graph.induced_subgraph([3, 1, 5]);
+        assert_eq!(subgraph.nodes().copied().collect::<Vec<_>>(), [3, 1, 5]);
+        assert_eq!(subgraph.edge_count(), 1);
+        assert!(subgraph.has_edge(&1, &3));
+        assert_eq!(subgraph.topological_sort(), Ok(vec![1, 3, 5]));
+    }
+
+    #[test]
+    fn test_transitive_reduction() {
+        let graph = from_edges(&[(1, 2), (2, 3), (1, 3), (3, 4), (1, 4), (2, 4)]);
+        let reduced = graph.transitive_reduction().unwrap();
+        let mut edges = reduced.edges().map(|(&a, &b)| (a, b)).collect::<Vec<_>>();
+        edges.sort();
+        assert_eq!(edges, [(1, 2), (2, 3), (3, 4)]);
+        assert!(from_edges(&[(1, 2), (2, 1)])
+            .transitive_reduction()
+            .is_err());
+    }
+}
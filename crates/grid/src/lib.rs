@@ -0,0 +1,352 @@
+use geometry::Point;
+use solution::ParseError;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A `(row, col)` position in a grid.
+pub type Pos = (usize, usize);
+
+/// A `(row, col)` step between positions.
+pub type Offset = (isize, isize);
+
+/// Up, right, down, left.
+pub const ORTHOGONAL: [Offset; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Up-right, down-right, down-left, up-left.
+pub const DIAGONAL: [Offset; 4] = [(-1, 1), (1, 1), (1, -1), (-1, -1)];
+
+/// All eight neighbors, clockwise from up.
+pub const ALL: [Offset; 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A row of the input didn't have the same width as the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaggedRow {
+    pub row: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for RaggedRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {} has width {}, expected {}",
+            self.row, self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for RaggedRow {}
+
+impl From<RaggedRow> for ParseError {
+    fn from(e: RaggedRow) -> Self {
+        ParseError::new(format!(
+            "expected {} columns, found {}",
+            e.expected, e.found
+        ))
+        .on_line(e.row + 1)
+    }
+}
+
+/// A rectangular grid stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl Grid<char> {
+    /// Parses one row per line, one cell per character.
+    pub fn parse(input: &str) -> Result<Self, RaggedRow> {
+        Self::parse_with(input, |c| c)
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Parses one row per line, converting each character with `f`.
+    pub fn parse_with(input: &str, mut f: impl FnMut(char) -> T) -> Result<Self, RaggedRow> {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        for (row, line) in input.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let found = cells.len() - before;
+            if row == 0 {
+                width = found;
+            } else if found != width {
+                return Err(RaggedRow {
+                    row,
+                    expected: width,
+                    found,
+                });
+            }
+            height += 1;
+        }
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): Pos) -> Option<&T> {
+        self.contains((row, col))
+            .then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): Pos) -> Option<&mut T> {
+        self.contains((row, col))
+            .then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get_point(&self, point: Point) -> Option<&T> {
+        self.get(point.to_pos()?)
+    }
+
+    pub fn get_point_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.get_mut(point.to_pos()?)
+    }
+
+    pub fn contains_point(&self, point: Point) -> bool {
+        point.to_pos().is_some_and(|pos| self.contains(pos))
+    }
+
+    /// The position one `offset` away from `pos`, if it's inside the grid.
+    pub fn step(&self, (row, col): Pos, (dr, dc): Offset) -> Option<Pos> {
+        let row = row.checked_add_signed(dr)?;
+        let col = col.checked_add_signed(dc)?;
+        self.contains((row, col)).then_some((row, col))
+    }
+
+    /// Positions one step away in each of `offsets` that are inside the grid.
+    pub fn neighbors<'a>(
+        &'a self,
+        pos: Pos,
+        offsets: &'a [Offset],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&offset| self.step(pos, offset))
+    }
+
+    /// Orthogonal neighbors inside the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, &ORTHOGONAL)
+    }
+
+    /// Orthogonal and diagonal neighbors inside the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, &ALL)
+    }
+
+    /// Positions starting at `pos` and repeatedly stepping by `offset`
+    /// until leaving the grid. Includes `pos` itself if it's inside.
+    pub fn ray(&self, pos: Pos, offset: Offset) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(self.contains(pos).then_some(pos), move |&p| {
+            self.step(p, offset)
+        })
+    }
+
+    /// The values along [`Grid::ray`].
+    pub fn ray_values(&self, pos: Pos, offset: Offset) -> impl Iterator<Item = &T> + '_ {
+        self.ray(pos, offset).map(|p| &self[p])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.ray_values((0, col), (1, 0))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, v)| *v == value)
+            .map(|(p, _)| p)
+    }
+
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): Pos) -> &T {
+        assert!(
+            self.contains((row, col)),
+            "{:?} is outside the grid",
+            (row, col)
+        );
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (row, col): Pos) -> &mut T {
+        assert!(
+            self.contains((row, col)),
+            "{:?} is outside the grid",
+            (row, col)
+        );
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "abc
+def";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn test_parse_ragged() {
+        let result = Grid::parse("abc\nde");
+        assert_eq!(
+            result,
+            Err(RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_ragged_parse_error() {
+        let err = ParseError::from(Grid::parse("abc\nabc\nab").unwrap_err());
+        assert_eq!(err.to_string(), "line 3: expected 3 columns, found 2");
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let grid = Grid::parse_with("12\n34", |c| c.to_digit(10).unwrap()).unwrap();
+        assert_eq!(grid.row(1), &[3, 4]);
+    }
+
+    #[test]
+    fn test_get() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.get((0, 0)), Some(&'a'));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert!(grid.contains_point(Point::new(1, 2)));
+        assert!(!grid.contains_point(Point::new(-1, 0)));
+        assert_eq!(grid.get_point(Point::new(1, 0)), Some(&'d'));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::parse(INPUT).unwrap();
+        let result = grid.neighbors4((0, 0)).collect::<Vec<_>>();
+        assert_eq!(result, vec![(0, 1), (1, 0)]);
+        let result = grid.neighbors8((1, 1)).collect::<Vec<_>>();
+        assert_eq!(result, vec![(0, 1), (0, 2), (1, 2), (1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn test_tiny_grid() {
+        let grid = Grid::parse("X").unwrap();
+        assert_eq!(grid.neighbors8((0, 0)).count(), 0);
+        assert_eq!(grid.ray((0, 0), (1, 1)).count(), 1);
+    }
+
+    #[test]
+    fn test_rays() {
+        let grid = Grid::parse(INPUT).unwrap();
+        let diagonal = grid.ray_values((0, 0), (1, 1)).collect::<String>();
+        assert_eq!(diagonal, "ae");
+        let backwards = grid.ray_values((1, 2), (0, -1)).collect::<String>();
+        assert_eq!(backwards, "fed");
+        let column = grid.column(1).collect::<String>();
+        assert_eq!(column, "be");
+        assert_eq!(grid.ray((5, 5), (1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = Grid::parse("a.a\n.a.").unwrap();
+        let result = grid.find_all(&'a').collect::<Vec<_>>();
+        assert_eq!(result, vec![(0, 0), (0, 2), (1, 1)]);
+        assert_eq!(grid.find(&'.'), Some((0, 1)));
+        assert_eq!(grid.find(&'z'), None);
+    }
+}
@@ -0,0 +1,313 @@
+use nom::character::complete::{char, digit1, one_of, satisfy, space0, space1};
+use nom::combinator::{all_consuming, cut, map, not, opt, peek, recognize};
+use nom::error::ErrorKind;
+use nom::multi::many0;
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated};
+use solution::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+/// What a parser was looking for when it failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    Number,
+    Whitespace,
+    Char(char),
+    End,
+    Other(ErrorKind),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Number => write!(f, "a number"),
+            Expected::Whitespace => write!(f, "whitespace"),
+            Expected::Char(c) => write!(f, "`{}`", c),
+            Expected::End => write!(f, "end of line"),
+            Expected::Other(kind) => write!(f, "{}", kind.description()),
+        }
+    }
+}
+
+/// A nom error that keeps the remaining input, so it can be located.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<'a> {
+    pub input: &'a str,
+    pub expected: Expected,
+}
+
+impl<'a> nom::error::ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        let expected = match kind {
+            ErrorKind::Digit => Expected::Number,
+            ErrorKind::Space | ErrorKind::MultiSpace => Expected::Whitespace,
+            ErrorKind::Eof => Expected::End,
+            kind => Expected::Other(kind),
+        };
+        Self { input, expected }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'a str, c: char) -> Self {
+        Self {
+            input,
+            expected: Expected::Char(c),
+        }
+    }
+}
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T, Error<'a>>;
+
+/// A number without a sign, like `42`.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<'_, T> {
+    number(digit1, input)
+}
+
+/// A number with an optional sign, like `-42` or `+42`.
+pub fn signed<T: FromStr>(input: &str) -> IResult<'_, T> {
+    number(recognize(pair(opt(one_of("+-")), digit1)), input)
+}
+
+/// Reads the digits `digits` matches as a `T`. Any failure, including
+/// digits running into letters like `12ab` or a value that doesn't fit,
+/// is reported as expecting a number where the token starts.
+fn number<'a, T: FromStr>(
+    digits: impl FnMut(&'a str) -> IResult<'a, &'a str>,
+    input: &'a str,
+) -> IResult<'a, T> {
+    let expected_number = || Error {
+        input,
+        expected: Expected::Number,
+    };
+    let (rest, text) = terminated(digits, not(satisfy(char::is_alphanumeric)))(input)
+        .map_err(|e| e.map(|_| expected_number()))?;
+    let value = text.strip_prefix('+').unwrap_or(text).parse();
+    let value = value.map_err(|_| nom::Err::Error(expected_number()))?;
+    Ok((rest, value))
+}
+
+/// Whitespace-separated numbers, like `7 6 -4 2 1`. Any word after the
+/// first number must be a number too, so `7 6 x` fails at the `x`.
+pub fn ints<T: FromStr>(input: &str) -> IResult<'_, Vec<T>> {
+    let word = satisfy(|c| c.is_alphanumeric() || c == '+' || c == '-');
+    list_of(preceded(pair(space1, peek(word)), cut(signed)))(input)
+}
+
+/// Numbers separated by `sep`, like `75,47,61` for `','`.
+pub fn list<'a, T: FromStr>(sep: char) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    list_of(preceded(delimited(space0, char(sep), space0), cut(signed)))
+}
+
+fn list_of<'a, T: FromStr>(
+    rest: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    map(pair(signed, many0(rest)), |(first, mut rest)| {
+        rest.insert(0, first);
+        rest
+    })
+}
+
+/// Two numbers separated by `sep`, like `47|53` for `'|'`.
+pub fn pair_of<'a, T: FromStr>(sep: char) -> impl FnMut(&'a str) -> IResult<'a, (T, T)> {
+    separated_pair(signed, delimited(space0, char(sep), space0), signed)
+}
+
+/// A number followed by a colon and a list of numbers, like `190: 10 19`.
+pub fn key_values<K: FromStr, V: FromStr>(input: &str) -> IResult<'_, (K, Vec<V>)> {
+    separated_pair(signed, terminated(char(':'), space0), ints)(input)
+}
+
+/// Runs `parser` over a whole line, ignoring surrounding spaces, and
+/// reports the column it stopped at on failure.
+pub fn parse_line<'a, T>(
+    line: &'a str,
+    parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> Result<T, ParseError> {
+    match all_consuming(delimited(space0, parser, space0))(line) {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::at_column(
+            line.len() - e.input.len() + 1,
+            format!("expected {}, found {}", e.expected, found(e.input)),
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new("incomplete input")),
+    }
+}
+
+fn found(input: &str) -> String {
+    match input.chars().next() {
+        None | Some('\r' | '\n') => "end of line".to_string(),
+        Some(c) if c.is_whitespace() => "whitespace".to_string(),
+        Some(_) => format!("`{}`", input.split(char::is_whitespace).next().unwrap()),
+    }
+}
+
+/// Parses every line of `input` with `parser`.
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(line, &mut parser).map_err(|e| e.on_line(i + 1)))
+        .collect()
+}
+
+/// A run of non-blank lines, remembering where it starts in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// 1-based line number of the first line.
+    pub line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    /// Like [`lines`], with errors located in the whole input.
+    pub fn lines<T>(
+        &self,
+        parser: impl FnMut(&'a str) -> IResult<'a, T>,
+    ) -> Result<Vec<T>, ParseError> {
+        lines(self.text, parser).map_err(|e| e.offset_lines(self.line - 1))
+    }
+}
+
+/// Splits `input` into sections separated by one or more blank lines.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let blank = line.trim().is_empty();
+        match (start, blank) {
+            (None, false) => start = Some((i + 1, offset)),
+            (Some((line, from)), true) => {
+                sections.push(Section {
+                    line,
+                    text: input[from..offset].trim_end(),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    if let Some((line, from)) = start {
+        sections.push(Section {
+            line,
+            text: input[from..].trim_end(),
+        });
+    }
+    sections
+}
+
+/// Splits `input` into exactly `N` sections.
+pub fn split_sections<const N: usize>(input: &str) -> Result<[Section<'_>; N], ParseError> {
+    let sections = sections(input);
+    let found = sections.len();
+    sections.try_into().map_err(|_| {
+        ParseError::new(format!(
+            "expected {} sections separated by blank lines, found {}",
+            N, found
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(parse_line("42", unsigned::<u32>), Ok(42));
+        assert_eq!(parse_line("-42", signed::<i32>), Ok(-42));
+        assert_eq!(parse_line("+42", signed::<i32>), Ok(42));
+        assert_eq!(
+            parse_line("-42", unsigned::<u32>).unwrap_err().to_string(),
+            "column 1: expected a number, found `-42`"
+        );
+        assert_eq!(
+            parse_line("300", unsigned::<u8>).unwrap_err().to_string(),
+            "column 1: expected a number, found `300`"
+        );
+        assert_eq!(
+            parse_line("12ab", unsigned::<u32>).unwrap_err().to_string(),
+            "column 1: expected a number, found `12ab`"
+        );
+        assert_eq!(
+            parse_line("-4x", signed::<i32>).unwrap_err().to_string(),
+            "column 1: expected a number, found `-4x`"
+        );
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(
+            parse_line("  7 6  -4 2 ", ints::<i32>),
+            Ok(vec![7, 6, -4, 2])
+        );
+        assert_eq!(
+            parse_line("7 6 x 2", ints::<i32>).unwrap_err().to_string(),
+            "column 5: expected a number, found `x`"
+        );
+    }
+
+    #[test]
+    fn test_separated() {
+        assert_eq!(
+            parse_line("75,47,61", list::<i32>(',')),
+            Ok(vec![75, 47, 61])
+        );
+        assert_eq!(parse_line("47|53", pair_of::<i32>('|')), Ok((47, 53)));
+        assert_eq!(
+            parse_line("47-53", pair_of::<i32>('|'))
+                .unwrap_err()
+                .to_string(),
+            "column 3: expected `|`, found `-53`"
+        );
+    }
+
+    #[test]
+    fn test_key_values() {
+        assert_eq!(
+            parse_line("190: 10 19", key_values::<i64, i64>),
+            Ok((190, vec![10, 19]))
+        );
+        assert_eq!(
+            parse_line("190:", key_values::<i64, i64>)
+                .unwrap_err()
+                .to_string(),
+            "column 5: expected a number, found end of line"
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        let err = lines("1 2\n3 4 5\n6 x", ints::<i32>).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 3: expected a number, found `x`"
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let [first, second] = split_sections("a\nb\n\n\nc\n").unwrap();
+        assert_eq!(
+            first,
+            Section {
+                line: 1,
+                text: "a\nb"
+            }
+        );
+        assert_eq!(second, Section { line: 5, text: "c" });
+        let err = second.lines(ints::<i32>).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 5, column 1: expected a number, found `c`"
+        );
+        assert!(split_sections::<2>("a\nb").is_err());
+    }
+}
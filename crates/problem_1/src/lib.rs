@@ -1,11 +1,49 @@
+use parsing::ints;
+use solution::{Answer, ParseError, Solution};
 use std::collections::HashMap;
 
-pub fn part_1(input: &str) -> i32 {
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<Vec<i32>>;
+
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Historian Hysteria";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let rows = parsing::lines(input, ints)?;
+        if let Some(i) = rows.iter().position(|row| row.len() != 2) {
+            return Err(
+                ParseError::new(format!("expected two numbers, found {}", rows[i].len()))
+                    .on_line(i + 1),
+            );
+        }
+        Ok(rows)
+    }
+
+    fn part_1(rows: &Self::Input) -> Answer {
+        total_distance(rows).into()
+    }
+
+    fn part_2(rows: &Self::Input) -> Answer {
+        similarity_score(rows).into()
+    }
+}
+
+pub fn part_1(input: &str) -> Option<i32> {
+    Some(total_distance(&Day1::parse(input).ok()?))
+}
+
+pub fn part_2(input: &str) -> Option<i32> {
+    Some(similarity_score(&Day1::parse(input).ok()?))
+}
+
+fn total_distance(rows: &[Vec<i32>]) -> i32 {
     let mut columns = vec![
         ColumnType::ListInts(Vec::new()),
         ColumnType::ListInts(Vec::new()),
     ];
-    read_into_columns(input, &mut columns);
+    read_into_columns(rows, &mut columns);
 
     columns.iter_mut().for_each(|c| c.sort());
     columns[0]
@@ -16,12 +54,12 @@ pub fn part_1(input: &str) -> i32 {
         .sum()
 }
 
-pub fn part_2(input: &str) -> i32 {
+fn similarity_score(rows: &[Vec<i32>]) -> i32 {
     let mut columns = vec![
         ColumnType::ListInts(Vec::new()),
         ColumnType::FrequencyMapInts(HashMap::new()),
     ];
-    read_into_columns(input, &mut columns);
+    read_into_columns(rows, &mut columns);
 
     let vs = columns[0].as_slice();
     let freq_map = columns[1].as_freq_map();
@@ -66,22 +104,14 @@ impl ColumnType {
     }
 }
 
-fn read_into_columns(input: &str, columns: &mut [ColumnType]) {
-    input.lines().for_each(|line| {
-        let nums = read_ints_from_str(line);
+fn read_into_columns(rows: &[Vec<i32>], columns: &mut [ColumnType]) {
+    rows.iter().for_each(|nums| {
         nums.iter().enumerate().for_each(|(i, &num)| {
             columns[i].insert(num);
         });
     });
 }
 
-fn read_ints_from_str(input: &str) -> Vec<i32> {
-    input
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect()
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -95,7 +125,7 @@ mod test {
 3   9
 3   3
 ";
-        assert_eq!(part_1(input), 11);
+        assert_eq!(part_1(input), Some(11));
     }
 
     #[test]
@@ -107,6 +137,21 @@ mod test {
 3   9
 3   3
 ";
-        assert_eq!(part_2(input), 31);
+        assert_eq!(part_2(input), Some(31));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let result = Day1::parse("3   4\n4   x\n");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 2, column 5: expected a number, found `x`"
+        );
+        let result = Day1::parse("3   4\n4\n");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 2: expected two numbers, found 1"
+        );
+        assert_eq!(part_1("3   4\n4\n"), None);
     }
 }
@@ -1,70 +1,75 @@
+use geometry::Point;
+use grid::Grid;
+use solution::{Answer, ParseError, Solution};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Grid<char>;
+
+    const DAY: u32 = 10;
+    const TITLE: &'static str = "Hoof It";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(Grid::parse(input)?)
+    }
+
+    fn part_1(grid: &Self::Input) -> Answer {
+        total_score(grid).into()
+    }
+
+    fn part_2(grid: &Self::Input) -> Answer {
+        total_rating(grid).into()
+    }
+}
+
 pub fn part_1(input: &str) -> Option<i32> {
-    let grid = input
-        .lines()
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
+    Some(total_score(&Day10::parse(input).ok()?))
+}
 
+fn total_score(grid: &Grid<char>) -> i32 {
     let mut scores = 0;
-    for row in 0..grid.len() {
-        for col in 0..grid[0].len() {
-            if grid[row][col] == '0' {
-                scores += trails(&grid, '0', row, col)
-                    .iter()
-                    .collect::<std::collections::HashSet<_>>()
-                    .len();
-            }
-        }
+    for trailhead in grid.find_all(&'0') {
+        scores += trails(grid, '0', Point::from_pos(trailhead))
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len();
     }
 
-    Some(scores as i32)
+    scores as i32
 }
 
-fn trails(grid: &[Vec<char>], current: char, row: usize, col: usize) -> Vec<(usize, usize)> {
+fn trails(grid: &Grid<char>, current: char, pos: Point) -> Vec<Point> {
     if current == '9' {
-        return vec![(row, col)];
+        return vec![pos];
     }
 
     // find next step
     let next = (current as u8 + 1) as char;
 
-    let mut ends: Vec<(usize, usize)> = vec![];
-    if row > 0 && grid[row - 1][col] == next {
-        ends.extend_from_slice(&trails(grid, next, row - 1, col));
-    }
-
-    if row < grid.len() - 1 && grid[row + 1][col] == next {
-        ends.extend_from_slice(&trails(grid, next, row + 1, col));
-    }
-
-    if col > 0 && grid[row][col - 1] == next {
-        ends.extend_from_slice(&trails(grid, next, row, col - 1));
-    }
-
-    if col < grid[0].len() - 1 && grid[row][col + 1] == next {
-        ends.extend_from_slice(&trails(grid, next, row, col + 1));
+    let mut ends: Vec<Point> = vec![];
+    for neighbor in pos.neighbors4() {
+        if grid.get_point(neighbor) == Some(&next) {
+            ends.extend_from_slice(&trails(grid, next, neighbor));
+        }
     }
 
     ends
 }
 
 pub fn part_2(input: &str) -> Option<i32> {
-    let grid = input
-        .lines()
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
+    Some(total_rating(&Day10::parse(input).ok()?))
+}
 
+fn total_rating(grid: &Grid<char>) -> i32 {
     let mut scores = 0;
-    for row in 0..grid.len() {
-        for col in 0..grid[0].len() {
-            if grid[row][col] == '0' {
-                scores += rating(&grid, '0', row, col);
-            }
-        }
+    for trailhead in grid.find_all(&'0') {
+        scores += rating(grid, '0', Point::from_pos(trailhead));
     }
-    Some(scores as i32)
+    scores as i32
 }
 
-fn rating(grid: &[Vec<char>], current: char, row: usize, col: usize) -> usize {
+fn rating(grid: &Grid<char>, current: char, pos: Point) -> usize {
     if current == '9' {
         return 1;
     }
@@ -73,20 +78,10 @@ fn rating(grid: &[Vec<char>], current: char, row: usize, col: usize) -> usize {
     let next = (current as u8 + 1) as char;
 
     let mut count = 0;
-    if row > 0 && grid[row - 1][col] == next {
-        count += rating(grid, next, row - 1, col);
-    }
-
-    if row < grid.len() - 1 && grid[row + 1][col] == next {
-        count += rating(grid, next, row + 1, col);
-    }
-
-    if col > 0 && grid[row][col - 1] == next {
-        count += rating(grid, next, row, col - 1);
-    }
-
-    if col < grid[0].len() - 1 && grid[row][col + 1] == next {
-        count += rating(grid, next, row, col + 1);
+    for neighbor in pos.neighbors4() {
+        if grid.get_point(neighbor) == Some(&next) {
+            count += rating(grid, next, neighbor);
+        }
     }
 
     count
@@ -1,93 +1,108 @@
+use parsing::ints;
+use solution::{Answer, ParseError, Solution};
 use std::collections::HashMap;
 
-pub fn part_1(input: &str) -> Option<usize> {
-    let inputs = input
-        .split_ascii_whitespace()
-        .map(|n| n.parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
+pub use rules::{digits, split_digits, Overflow, Rule, RuleSet};
 
-    let freq = freq_map(&inputs);
-    let mut solver = Solver::default();
-    Some(tally(&solver.solve(&freq)))
+mod rules;
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<u64>;
+
+    const DAY: u32 = 11;
+    const TITLE: &'static str = "Plutonian Pebbles";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(parsing::lines(input, ints)?.concat())
+    }
+
+    fn part_1(stones: &Self::Input) -> Answer {
+        Solver::default().count_all(stones, 25).into()
+    }
+
+    fn part_2(stones: &Self::Input) -> Answer {
+        Solver::default().count_all(stones, 75).into()
+    }
 }
 
-// let MEMO: HashMap<u64, Vec<u64>> = HashMap::new();
+pub fn part_1(input: &str) -> Option<u64> {
+    Solver::default().count_all(&Day11::parse(input).ok()?, 25)
+}
 
-#[derive(Default)]
-struct Solver {
-    memo: HashMap<u64, Vec<u64>>,
+/// Counts stones after any number of blinks without expanding them,
+/// remembering the count for each `(stone, blinks)` pair it has seen so
+/// stones that keep turning up are only worked out once.
+#[derive(Debug, Default)]
+pub struct Solver {
+    rules: RuleSet,
+    memo: HashMap<(u64, usize), Option<u64>>,
 }
 
 impl Solver {
-    pub fn solve_25(&mut self, input: u64) -> Vec<u64> {
-        if let Some(v) = self.memo.get(&input) {
-            return v.clone();
+    pub fn new(rules: RuleSet) -> Self {
+        Self {
+            rules,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// How many stones `stone` becomes after `blinks` blinks, or `None` if a
+    /// stone or the count overflows a `u64`. With the standard rules the
+    /// count overflows after about 100 blinks.
+    pub fn count(&mut self, stone: u64, blinks: usize) -> Option<u64> {
+        if blinks == 0 {
+            return Some(1);
         }
-        let mut inputs = vec![input];
-        for _ in 0..25 {
-            inputs = apply_rules(&inputs);
+        if let Some(&count) = self.memo.get(&(stone, blinks)) {
+            return count;
         }
-        self.memo.insert(input, inputs.clone());
-        inputs
+        let count = self.rules.apply(stone).ok().and_then(|next| {
+            next.into_iter().try_fold(0u64, |total, next| {
+                total.checked_add(self.count(next, blinks - 1)?)
+            })
+        });
+        self.memo.insert((stone, blinks), count);
+        count
     }
 
-    pub fn solve(&mut self, freq: &HashMap<u64, usize>) -> HashMap<u64, usize> {
-        freq.iter().fold(HashMap::new(), |mut acc, (k, v)| {
-            let new_inputs = self.solve_25(*k);
-            for n in new_inputs {
-                *acc.entry(n).or_insert(0) += v;
-            }
-            acc
+    pub fn count_all(&mut self, stones: &[u64], blinks: usize) -> Option<u64> {
+        stones.iter().try_fold(0u64, |total, &stone| {
+            total.checked_add(self.count(stone, blinks)?)
         })
     }
 }
 
-fn freq_map(inputs: &[u64]) -> HashMap<u64, usize> {
-    inputs.iter().fold(HashMap::new(), |mut acc, &n| {
-        *acc.entry(n).or_insert(0) += 1;
-        acc
-    })
-}
-
-fn tally(inputs: &HashMap<u64, usize>) -> usize {
-    inputs.values().sum()
+/// How many of each stone there are after each blink under `rules`,
+/// starting with `stones` as they are. Ends if a stone or count overflows.
+pub fn frequencies<'a>(
+    rules: &'a RuleSet,
+    stones: &[u64],
+) -> impl Iterator<Item = HashMap<u64, u64>> + 'a {
+    std::iter::successors(Some(freq_map(stones)), |freq| blink(rules, freq))
 }
 
-fn evaluate_rules(input: u64) -> Vec<u64> {
-    if input == 0 {
-        return vec![1];
-    }
-    let input_str = input.to_string();
-    if input_str.len() % 2 == 0 {
-        // split word in half
-        let half = input_str.len() / 2;
-        let (first, second) = input_str.split_at(half);
-        let first = first.parse::<u64>().unwrap();
-        let second = second.parse::<u64>().unwrap();
-        return vec![first, second];
+fn blink(rules: &RuleSet, freq: &HashMap<u64, u64>) -> Option<HashMap<u64, u64>> {
+    let mut next = HashMap::new();
+    for (&stone, &count) in freq {
+        for stone in rules.apply(stone).ok()? {
+            let entry = next.entry(stone).or_insert(0u64);
+            *entry = entry.checked_add(count)?;
+        }
     }
-    vec![input * 2024]
+    Some(next)
 }
 
-fn apply_rules(inputs: &[u64]) -> Vec<u64> {
-    inputs
-        .iter()
-        .flat_map(|input| evaluate_rules(*input))
-        .collect()
+fn freq_map(inputs: &[u64]) -> HashMap<u64, u64> {
+    inputs.iter().fold(HashMap::new(), |mut acc, &n| {
+        *acc.entry(n).or_insert(0) += 1;
+        acc
+    })
 }
 
-pub fn part_2(input: &str) -> Option<usize> {
-    let inputs = input
-        .split_ascii_whitespace()
-        .map(|n| n.parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
-
-    let freq = freq_map(&inputs);
-    let mut solver = Solver::default();
-    let freq = solver.solve(&freq); // 25
-    let freq = solver.solve(&freq); // 50
-    let freq = solver.solve(&freq); // 75
-    Some(tally(&freq))
+pub fn part_2(input: &str) -> Option<u64> {
+    Solver::default().count_all(&Day11::parse(input).ok()?, 75)
 }
 
 #[cfg(test)]
@@ -102,9 +117,105 @@ mod tests {
         assert_eq!(result, Some(55312));
     }
 
+    #[test]
+    fn test_solver() {
+        let mut solver = Solver::default();
+        let counts = (0..5).map(|blinks| solver.count(0, blinks));
+        assert_eq!(counts.collect::<Vec<_>>(), [1, 1, 1, 2, 4].map(Some));
+        assert_eq!(solver.count_all(&[125, 17], 6), Some(22));
+        assert_eq!(solver.count(0, 500), None);
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let rules = RuleSet::standard();
+        let steps = frequencies(&rules, &[125, 17]).take(7).collect::<Vec<_>>();
+        assert_eq!(steps[0], HashMap::from([(125, 1), (17, 1)]));
+        assert_eq!(
+            steps[3],
+            HashMap::from([(512072, 1), (1, 1), (20, 1), (24, 1), (28676032, 1)])
+        );
+        let mut solver = Solver::default();
+        for (blinks, freq) in steps.iter().enumerate() {
+            let total = freq.values().sum::<u64>();
+            assert_eq!(solver.count_all(&[125, 17], blinks), Some(total));
+        }
+        assert!(frequencies(&rules, &[0]).nth(500).is_none());
+    }
+
+    /// Stone lists from a small linear congruential generator, mixing small
+    /// stones with ones up to 12 digits.
+    fn stone_lists() -> impl Iterator<Item = Vec<u64>> {
+        let mut state = 2024u64;
+        (0..50).map(move |i| {
+            (0..1 + i % 5)
+                .map(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    let digits = (state >> 60) as u32 % 13;
+                    (state >> 20) % 10u64.pow(digits)
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_other_rules() {
+        // Without the split, stones only ever grow and never repeat.
+        let rules = RuleSet::new(vec![Rule::replace(0, 1), Rule::multiply(3)]);
+        let mut solver = Solver::new(rules);
+        assert_eq!(solver.count_all(&[0, 1], 30), Some(2));
+        assert_eq!(solver.count(1, 50), None);
+        // 125 17 -> 875 1 7 -> 6125 7 49 -> 61 25 49 4 9
+        let rules = RuleSet::new(vec![
+            Rule::replace(0, 1),
+            Rule::split_even_digits(),
+            Rule::multiply(7),
+        ]);
+        let mut solver = Solver::new(rules);
+        assert_eq!(solver.count_all(&[125, 17], 3), Some(5));
+        assert_eq!(solver.count_all(&[125, 17], 6), Some(15));
+    }
+
+    #[test]
+    fn test_solver_matches_naive() {
+        let rules = RuleSet::standard();
+        for stones in stone_lists() {
+            let mut solver = Solver::default();
+            let mut expanded = stones.clone();
+            for blinks in 0..=15 {
+                let count = expanded.len() as u64;
+                assert_eq!(
+                    solver.count_all(&stones, blinks),
+                    Some(count),
+                    "{:?}",
+                    stones
+                );
+                let freq = frequencies(&rules, &stones).nth(blinks).unwrap();
+                assert_eq!(freq, freq_map(&expanded), "{:?}", stones);
+                expanded = rules.blink(&expanded).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_overflow() {
+        let mut solver = Solver::default();
+        assert_eq!(
+            solver.count_all(&[125, 17], 105),
+            Some(18325301328443575078)
+        );
+        assert_eq!(solver.count_all(&[125, 17], 106), None);
+        // The memo remembers the overflow rather than the last count.
+        assert_eq!(solver.count_all(&[125, 17], 106), None);
+        assert_eq!(
+            solver.count_all(&[125, 17], 105),
+            Some(18325301328443575078)
+        );
+    }
+
     #[test]
     fn test_part_2() {
         let result = part_2(INPUT);
-        assert_eq!(result, None);
+        assert_eq!(result, Some(65601038650482));
     }
 }
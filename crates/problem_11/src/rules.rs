@@ -0,0 +1,253 @@
+use std::fmt;
+
+/// A stone a rule couldn't transform without overflowing a `u64`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    pub stone: u64,
+    pub rule: String,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rule `{}` overflows on stone {}", self.rule, self.stone)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+type Predicate = Box<dyn Fn(u64) -> bool + Send + Sync>;
+type Transform = Box<dyn Fn(u64) -> Option<Vec<u64>> + Send + Sync>;
+
+/// Stones that `applies` accepts become the stones `transform` returns, or
+/// overflow if it returns `None`.
+pub struct Rule {
+    name: String,
+    applies: Predicate,
+    transform: Transform,
+    catch_all: bool,
+}
+
+impl Rule {
+    pub fn new(
+        name: impl Into<String>,
+        applies: impl Fn(u64) -> bool + Send + Sync + 'static,
+        transform: impl Fn(u64) -> Option<Vec<u64>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            applies: Box::new(applies),
+            transform: Box::new(transform),
+            catch_all: false,
+        }
+    }
+
+    /// A rule for every stone, so no rule after it in a [`RuleSet`] is ever
+    /// tried.
+    pub fn catch_all(
+        name: impl Into<String>,
+        transform: impl Fn(u64) -> Option<Vec<u64>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            catch_all: true,
+            ..Self::new(name, |_| true, transform)
+        }
+    }
+
+    /// `from` becomes `to`.
+    pub fn replace(from: u64, to: u64) -> Self {
+        Self::new(
+            format!("{} -> {}", from, to),
+            move |stone| stone == from,
+            move |_| Some(vec![to]),
+        )
+    }
+
+    /// A stone with an even number of digits splits into its left and right
+    /// halves.
+    pub fn split_even_digits() -> Self {
+        Self::new(
+            "split even digits",
+            |stone| digits(stone).is_multiple_of(2),
+            |stone| {
+                let (left, right) = split_digits(stone, digits(stone) / 2);
+                Some(vec![left, right])
+            },
+        )
+    }
+
+    /// Any stone is multiplied by `factor`.
+    pub fn multiply(factor: u64) -> Self {
+        Self::catch_all(format!("* {}", factor), move |stone| {
+            Some(vec![stone.checked_mul(factor)?])
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_catch_all(&self) -> bool {
+        self.catch_all
+    }
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Rule").field(&self.name).finish()
+    }
+}
+
+/// Rules tried in order, the first that applies deciding what a stone
+/// becomes.
+#[derive(Debug)]
+pub struct RuleSet(Vec<Rule>);
+
+impl RuleSet {
+    /// # Panics
+    ///
+    /// If a rule comes after a catch-all, as it would never apply.
+    pub fn new(rules: Vec<Rule>) -> Self {
+        rules.into_iter().fold(Self(Vec::new()), Self::with)
+    }
+
+    /// The puzzle's rules: `0` becomes `1`, even-digit stones split, and
+    /// everything else is multiplied by 2024.
+    pub fn standard() -> Self {
+        Self(vec![
+            Rule::replace(0, 1),
+            Rule::split_even_digits(),
+            Rule::multiply(2024),
+        ])
+    }
+
+    /// Adds `rule` after the others.
+    ///
+    /// # Panics
+    ///
+    /// If the last rule is a catch-all, as `rule` would never apply.
+    pub fn with(mut self, rule: Rule) -> Self {
+        if let Some(last) = self.0.last().filter(|last| last.catch_all) {
+            panic!(
+                "rule `{}` would never apply: it comes after the catch-all `{}`",
+                rule.name, last.name
+            );
+        }
+        self.0.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.0
+    }
+
+    /// What `stone` becomes after one blink. A stone no rule applies to
+    /// stays as it is.
+    pub fn apply(&self, stone: u64) -> Result<Vec<u64>, Overflow> {
+        let Some(rule) = self.0.iter().find(|rule| (rule.applies)(stone)) else {
+            return Ok(vec![stone]);
+        };
+        (rule.transform)(stone).ok_or_else(|| Overflow {
+            stone,
+            rule: rule.name.clone(),
+        })
+    }
+
+    /// Blinks once, expanding every stone.
+    pub fn blink(&self, stones: &[u64]) -> Result<Vec<u64>, Overflow> {
+        let mut next = Vec::with_capacity(stones.len());
+        for &stone in stones {
+            next.extend(self.apply(stone)?);
+        }
+        Ok(next)
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// The number of decimal digits in `n`.
+pub fn digits(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// Splits `n` into the digits before and the last `right` digits.
+pub fn split_digits(n: u64, right: u32) -> (u64, u64) {
+    let shift = 10u64.pow(right);
+    (n / shift, n % shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The rules as first written, splitting through strings.
+    fn by_strings(stone: u64) -> Vec<u64> {
+        if stone == 0 {
+            return vec![1];
+        }
+        let s = stone.to_string();
+        if s.len().is_multiple_of(2) {
+            let (left, right) = s.split_at(s.len() / 2);
+            return vec![left.parse().unwrap(), right.parse().unwrap()];
+        }
+        vec![stone * 2024]
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digits(0), 1);
+        assert_eq!(digits(9), 1);
+        assert_eq!(digits(10), 2);
+        assert_eq!(digits(u64::MAX), 20);
+        assert_eq!(split_digits(253000, 3), (253, 0));
+        assert_eq!(split_digits(1000, 2), (10, 0));
+    }
+
+    #[test]
+    fn test_standard_matches_strings() {
+        let rules = RuleSet::standard();
+        let edges = (0..19).flat_map(|k| [10u64.pow(k) - 1, 10u64.pow(k), 10u64.pow(k) + 7]);
+        for stone in edges.chain([17, 125, 2024, 28676032, 9_999_999_999_999]) {
+            if stone.checked_mul(2024).is_none() && !digits(stone).is_multiple_of(2) {
+                continue;
+            }
+            assert_eq!(rules.apply(stone).unwrap(), by_strings(stone), "{}", stone);
+        }
+        assert_eq!(rules.apply(u64::MAX).unwrap(), [1844674407, 3709551615]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let rules = RuleSet::standard();
+        let err = rules.apply(10u64.pow(18)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "rule `* 2024` overflows on stone 1000000000000000000"
+        );
+        assert!(rules.blink(&[0, 10u64.pow(18)]).is_err());
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = RuleSet::new(vec![Rule::replace(0, 1), Rule::multiply(3)]);
+        assert_eq!(rules.blink(&[0, 1, 5]).unwrap(), [1, 3, 15]);
+        let rules = RuleSet::new(vec![Rule::split_even_digits()]);
+        assert_eq!(rules.blink(&[7, 1234]).unwrap(), [7, 12, 34]);
+        let odd = Rule::new("odd -> 2 2", |stone| stone % 2 == 1, |_| Some(vec![2, 2]));
+        let rules = RuleSet::new(vec![odd]).with(Rule::replace(2, 0));
+        assert_eq!(rules.blink(&[3, 2]).unwrap(), [2, 2, 0]);
+        assert_eq!(rules.rules()[1].name(), "2 -> 0");
+        assert!(RuleSet::standard().rules()[2].is_catch_all());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "rule `* 7` would never apply: it comes after the catch-all `* 2024`"
+    )]
+    fn test_rule_after_catch_all() {
+        let _ = RuleSet::standard().with(Rule::multiply(7));
+    }
+}
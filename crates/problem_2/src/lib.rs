@@ -1,20 +1,43 @@
-pub fn part_1(input: &str) -> i32 {
-    input
-        .lines()
-        .filter(|line: &&str| {
-            let line = line
-                .split_whitespace()
-                .map(|s| s.parse::<i32>().unwrap())
-                .collect::<Vec<i32>>();
-            level_safe(&line)
-        })
-        .count() as i32
+use parsing::ints;
+use solution::{Answer, ParseError, Solution};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<i32>>;
+
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Red-Nosed Reports";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parsing::lines(input, ints)
+    }
+
+    fn part_1(reports: &Self::Input) -> Answer {
+        count_safe(reports).into()
+    }
+
+    fn part_2(reports: &Self::Input) -> Answer {
+        count_safe_dampened(reports).into()
+    }
+}
+
+pub fn part_1(input: &str) -> Option<i32> {
+    Some(count_safe(&Day2::parse(input).ok()?))
+}
+
+pub fn part_2(input: &str) -> Option<i32> {
+    Some(count_safe_dampened(&Day2::parse(input).ok()?))
+}
+
+fn count_safe(reports: &[Vec<i32>]) -> i32 {
+    reports.iter().filter(|line| level_safe(line)).count() as i32
 }
 
-pub fn part_2(input: &str) -> i32 {
-    input
-        .lines()
-        .filter(|line: &&str| level_safe_dampened(line))
+fn count_safe_dampened(reports: &[Vec<i32>]) -> i32 {
+    reports
+        .iter()
+        .filter(|line| level_safe_dampened(line))
         .count() as i32
 }
 
@@ -45,18 +68,13 @@ fn level_safe(line: &[i32]) -> bool {
     true
 }
 
-fn level_safe_dampened(line: &str) -> bool {
-    let nums = line
-        .split_whitespace()
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect::<Vec<i32>>();
-
-    if level_safe(&nums) {
+fn level_safe_dampened(nums: &[i32]) -> bool {
+    if level_safe(nums) {
         return true;
     }
 
     for i in 0..nums.len() {
-        let mut nums = nums.clone();
+        let mut nums = nums.to_vec();
         nums.remove(i);
         if level_safe(&nums) {
             return true;
@@ -78,7 +96,7 @@ mod test {
 1 3 2 4 5
 8 6 4 4 1
 1 3 6 7 9";
-        assert_eq!(part_1(input), 2);
+        assert_eq!(part_1(input), Some(2));
     }
 
     #[test]
@@ -89,6 +107,6 @@ mod test {
 1 3 2 4 5
 8 6 4 4 1
 1 3 6 7 9";
-        assert_eq!(part_2(input), 4);
+        assert_eq!(part_2(input), Some(4));
     }
 }
@@ -4,8 +4,30 @@ use nom::character::complete::char;
 use nom::combinator::{map, map_res};
 use nom::sequence::{delimited, preceded, separated_pair};
 use nom::IResult;
+use solution::{Answer, ParseError, Solution};
 use std::error::Error;
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "Mull It Over";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part_1(memory: &Self::Input) -> Answer {
+        part_1(memory).into()
+    }
+
+    fn part_2(memory: &Self::Input) -> Answer {
+        part_2(memory).into()
+    }
+}
+
 pub fn part_1(input: &str) -> Option<i32> {
     let mut result = 0;
     let mut input = input;
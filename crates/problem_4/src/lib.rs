@@ -1,154 +1,64 @@
-pub fn part_1(input: &str) -> Option<i32> {
-    let grid = Grid::new(input);
-
-    let mut xmas_count = 0;
-    for (y, row) in grid.enumerate() {
-        for (x, col) in row.iter().enumerate() {
-            if *col == 'X' {
-                xmas_count += grid.count_xmases_at(x, y);
-            }
-        }
-    }
-    Some(xmas_count)
-}
+use grid::{Grid, ALL, DIAGONAL};
+use solution::{Answer, ParseError, Solution};
 
-struct Grid {
-    grid: Vec<Vec<char>>,
-}
+pub struct Day4;
 
-impl Grid {
-    fn new(input: &str) -> Self {
-        let grid = input
-            .lines()
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect::<Vec<Vec<char>>>();
-        Self { grid }
-    }
+impl Solution for Day4 {
+    type Input = Grid<char>;
 
-    fn enumerate(&self) -> impl Iterator<Item = (usize, &Vec<char>)> {
-        self.grid.iter().enumerate()
-    }
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Ceres Search";
 
-    fn xmas_up(&self, x: usize, y: usize) -> bool {
-        if y < 3 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y - 1][x] == 'M'
-            && self.grid[y - 2][x] == 'A'
-            && self.grid[y - 3][x] == 'S'
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(Grid::parse(input)?)
     }
 
-    fn xmas_down(&self, x: usize, y: usize) -> bool {
-        if y > self.grid.len() - 4 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y + 1][x] == 'M'
-            && self.grid[y + 2][x] == 'A'
-            && self.grid[y + 3][x] == 'S'
+    fn part_1(grid: &Self::Input) -> Answer {
+        count_xmas(grid).into()
     }
 
-    fn xmas_left(&self, x: usize, y: usize) -> bool {
-        if x < 3 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y][x - 1] == 'M'
-            && self.grid[y][x - 2] == 'A'
-            && self.grid[y][x - 3] == 'S'
-    }
-
-    fn xmas_right(&self, x: usize, y: usize) -> bool {
-        if x > self.grid[0].len() - 4 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y][x + 1] == 'M'
-            && self.grid[y][x + 2] == 'A'
-            && self.grid[y][x + 3] == 'S'
-    }
-
-    fn xmas_up_left(&self, x: usize, y: usize) -> bool {
-        if y < 3 || x < 3 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y - 1][x - 1] == 'M'
-            && self.grid[y - 2][x - 2] == 'A'
-            && self.grid[y - 3][x - 3] == 'S'
-    }
-
-    fn xmas_up_right(&self, x: usize, y: usize) -> bool {
-        if y < 3 || x > self.grid[0].len() - 4 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y - 1][x + 1] == 'M'
-            && self.grid[y - 2][x + 2] == 'A'
-            && self.grid[y - 3][x + 3] == 'S'
+    fn part_2(grid: &Self::Input) -> Answer {
+        count_mas(grid).into()
     }
+}
 
-    fn xmas_down_left(&self, x: usize, y: usize) -> bool {
-        if y > self.grid.len() - 4 || x < 3 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y + 1][x - 1] == 'M'
-            && self.grid[y + 2][x - 2] == 'A'
-            && self.grid[y + 3][x - 3] == 'S'
-    }
+pub fn part_1(input: &str) -> Option<i32> {
+    Some(count_xmas(&Day4::parse(input).ok()?))
+}
 
-    fn xmas_down_right(&self, x: usize, y: usize) -> bool {
-        if y > self.grid.len() - 4 || x > self.grid[0].len() - 4 {
-            return false;
-        }
-        self.grid[y][x] == 'X'
-            && self.grid[y + 1][x + 1] == 'M'
-            && self.grid[y + 2][x + 2] == 'A'
-            && self.grid[y + 3][x + 3] == 'S'
-    }
+fn count_xmas(grid: &Grid<char>) -> i32 {
+    grid.find_all(&'X')
+        .map(|pos| count_xmases_at(grid, pos))
+        .sum()
+}
 
-    fn count_xmases_at(&self, x: usize, y: usize) -> i32 {
-        [
-            self.xmas_up(x, y),
-            self.xmas_down(x, y),
-            self.xmas_left(x, y),
-            self.xmas_right(x, y),
-            self.xmas_up_left(x, y),
-            self.xmas_up_right(x, y),
-            self.xmas_down_left(x, y),
-            self.xmas_down_right(x, y),
-        ]
-        .iter()
-        .filter(|&&x| x)
+fn count_xmases_at(grid: &Grid<char>, pos: grid::Pos) -> i32 {
+    ALL.iter()
+        .filter(|&&offset| {
+            grid.ray_values(pos, offset)
+                .copied()
+                .take(4)
+                .eq("XMAS".chars())
+        })
         .count() as i32
-    }
+}
 
-    fn mas_at(&self, x: usize, y: usize) -> bool {
-        if y < 1 || y > self.grid.len() - 2 || x < 1 || x > self.grid[0].len() - 2 {
-            return false;
-        }
-        self.grid[y][x] == 'A'
-            && ((self.grid[y - 1][x - 1] == 'M' && self.grid[y + 1][x + 1] == 'S')
-                || (self.grid[y - 1][x - 1] == 'S' && self.grid[y + 1][x + 1] == 'M'))
-            && ((self.grid[y - 1][x + 1] == 'M' && self.grid[y + 1][x - 1] == 'S')
-                || (self.grid[y - 1][x + 1] == 'S' && self.grid[y + 1][x - 1] == 'M'))
+fn mas_at(grid: &Grid<char>, pos: grid::Pos) -> bool {
+    if grid[pos] != 'A' {
+        return false;
     }
+    let [up_right, down_right, down_left, up_left] =
+        DIAGONAL.map(|offset| grid.step(pos, offset).map(|p| grid[p]));
+    let is_mas = |a, b| matches!((a, b), (Some('M'), Some('S')) | (Some('S'), Some('M')));
+    is_mas(up_left, down_right) && is_mas(up_right, down_left)
 }
 
 pub fn part_2(input: &str) -> Option<i32> {
-    let grid = Grid::new(input);
-
-    let mut mas_count = 0;
-    for (y, row) in grid.enumerate() {
-        for (x, _col) in row.iter().enumerate() {
-            if grid.mas_at(x, y) {
-                mas_count += 1;
-            }
-        }
-    }
-    Some(mas_count)
+    Some(count_mas(&Day4::parse(input).ok()?))
+}
+
+fn count_mas(grid: &Grid<char>) -> i32 {
+    grid.positions().filter(|&pos| mas_at(grid, pos)).count() as i32
 }
 
 #[cfg(test)]
@@ -177,4 +87,10 @@ MXMXAXMASX";
         let result = part_2(INPUT);
         assert_eq!(result, Some(9));
     }
+
+    #[test]
+    fn test_tiny_grid() {
+        assert_eq!(part_1("X"), Some(0));
+        assert_eq!(part_2("A"), Some(0));
+    }
 }
@@ -0,0 +1,110 @@
+use graph::Graph;
+
+/// How the rules that mention an update's pages order those pages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateOrder {
+    /// Exactly one order satisfies the rules.
+    Total(Vec<i32>),
+    /// Several orders satisfy the rules. `order` is one of them and
+    /// `unordered` two pages next to each other in it that no rule relates.
+    Partial {
+        order: Vec<i32>,
+        unordered: (i32, i32),
+        middle: Option<i32>,
+    },
+    /// No order satisfies the rules, because of this cycle.
+    Cycle(Vec<i32>),
+}
+
+impl UpdateOrder {
+    /// The middle page, if every valid order agrees on it.
+    pub fn middle(&self) -> Option<i32> {
+        match self {
+            UpdateOrder::Total(order) => order.get(order.len() / 2).copied(),
+            UpdateOrder::Partial { middle, .. } => *middle,
+            UpdateOrder::Cycle(_) => None,
+        }
+    }
+}
+
+/// Checks what the rules restricted to the pages of `update` say about
+/// their order, given the rules as a [`PageRules::graph`].
+///
+/// [`PageRules::graph`]: crate::PageRules::graph
+pub fn analyze(rules: &Graph<i32>, update: &[i32]) -> UpdateOrder {
+    let graph = rules.induced_subgraph(update.iter().copied());
+    let order = match graph.topological_sort() {
+        Ok(order) => order,
+        Err(cycle) => return UpdateOrder::Cycle(cycle.0),
+    };
+    // A sorted order is the only one exactly when each page must directly
+    // precede the next.
+    let unordered = order
+        .windows(2)
+        .find(|pair| !graph.has_edge(&pair[0], &pair[1]))
+        .map(|pair| (pair[0], pair[1]));
+    match unordered {
+        None => UpdateOrder::Total(order),
+        Some(unordered) => {
+            // A page sits at position `k` in every valid order when exactly
+            // `k` pages must come before it and the rest must come after.
+            let k = order.len() / 2;
+            let middle = order.iter().copied().find(|page| {
+                graph.ancestors(page).len() == k
+                    && graph.descendants(page).len() == order.len() - 1 - k
+            });
+            UpdateOrder::Partial {
+                order,
+                unordered,
+                middle,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PageRules;
+
+    fn page_rules(pairs: &[(i32, i32)]) -> Graph<i32> {
+        pairs.iter().copied().collect::<PageRules>().graph()
+    }
+
+    #[test]
+    fn test_total() {
+        let rules = page_rules(&[(97, 75), (75, 47), (97, 47), (47, 61), (13, 29)]);
+        let result = analyze(&rules, &[75, 97, 47, 61]);
+        assert_eq!(result, UpdateOrder::Total(vec![97, 75, 47, 61]));
+        assert_eq!(result.middle(), Some(47));
+    }
+
+    #[test]
+    fn test_partial() {
+        let rules = page_rules(&[(1, 2), (2, 3), (3, 4), (3, 5)]);
+        let result = analyze(&rules, &[5, 4, 3, 2, 1]);
+        assert_eq!(
+            result,
+            UpdateOrder::Partial {
+                order: vec![1, 2, 3, 5, 4],
+                unordered: (5, 4),
+                middle: Some(3),
+            }
+        );
+        let result = analyze(&page_rules(&[(1, 2), (1, 3)]), &[3, 2, 1]);
+        assert_eq!(result.middle(), None);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let rules = page_rules(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        let result = analyze(&rules, &[4, 3, 2, 1]);
+        assert_eq!(result, UpdateOrder::Cycle(vec![3, 1, 2]));
+        assert_eq!(result.middle(), None);
+        // The cycle only matters when all of its pages are in the update.
+        assert!(matches!(analyze(&rules, &[1, 2, 4]), UpdateOrder::Total(_)));
+        // Nor does the order the rules are listed in.
+        let shuffled = page_rules(&[(4, 1), (3, 1), (2, 3), (1, 2)]);
+        assert_eq!(analyze(&shuffled, &[4, 3, 2, 1]), result);
+    }
+}
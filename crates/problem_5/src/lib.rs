@@ -1,38 +1,87 @@
-use std::collections::{HashMap, HashSet};
+use graph::{Cycle, Graph};
+use parsing::{list, pair_of, split_sections};
+use solution::{Answer, ParseError, Solution};
 
-type Rules = HashMap<String, HashSet<String>>;
+pub use analysis::{analyze, UpdateOrder};
+pub use rules::PageRules;
 
-pub fn part_1(input: &str) -> Option<i32> {
-    let (part_1, part_2) = input.split_once("\n\n").unwrap();
-    let mut sum = 0;
-    let rules = rules(part_1);
-    for line in part_2.lines() {
-        if let Some(middle) = get_middle_if_correct(line, &rules) {
-            sum += middle;
-        }
-    }
-    Some(sum)
+mod analysis;
+mod rules;
+
+pub struct Day5;
+
+pub struct PrintQueue {
+    rules: PageRules,
+    graph: Graph<i32>,
+    updates: Vec<Vec<i32>>,
 }
 
-fn rules(input: &str) -> Rules {
-    let mut rules = HashMap::new();
-    for line in input.lines() {
-        let (key, value) = line.split_once("|").unwrap();
-        let key = key.to_string();
-        let set = rules.entry(key.clone()).or_insert(HashSet::new());
-        set.insert(value.to_string());
+impl PrintQueue {
+    pub fn rules(&self) -> &PageRules {
+        &self.rules
+    }
+
+    pub fn updates(&self) -> &[Vec<i32>] {
+        &self.updates
     }
-    rules
+
+    /// How the rules order each update, in input order.
+    pub fn analyze(&self) -> impl Iterator<Item = UpdateOrder> + '_ {
+        self.updates
+            .iter()
+            .map(|update| analyze(&self.graph, update))
+    }
+
+    /// The pages of `update` in an order that follows every rule between
+    /// them, or the cycle that rules that out.
+    pub fn reorder(&self, update: &[i32]) -> Result<Vec<i32>, Cycle<i32>> {
+        self.graph
+            .induced_subgraph(update.iter().copied())
+            .topological_sort()
+    }
+}
+
+impl Solution for Day5 {
+    type Input = PrintQueue;
+
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "Print Queue";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let [rules_section, updates_section] = split_sections(input)?;
+        let rules: PageRules = rules_section.lines(pair_of('|'))?.into_iter().collect();
+        let graph = rules.graph();
+        let updates = updates_section.lines(list(','))?;
+        Ok(PrintQueue {
+            rules,
+            graph,
+            updates,
+        })
+    }
+
+    fn part_1(queue: &Self::Input) -> Answer {
+        sum_correct_middles(queue).into()
+    }
+
+    fn part_2(queue: &Self::Input) -> Answer {
+        sum_reordered_middles(queue).into()
+    }
+}
+
+pub fn part_1(input: &str) -> Option<i32> {
+    Some(sum_correct_middles(&Day5::parse(input).ok()?))
 }
 
-fn pages(input: &str) -> Vec<i32> {
-    input
-        .split(",")
-        .map(|page| page.parse::<i32>().unwrap())
-        .collect()
+fn sum_correct_middles(queue: &PrintQueue) -> i32 {
+    queue
+        .updates
+        .iter()
+        .filter(|pages| queue.rules.is_ordered(pages))
+        .map(|pages| middle_page(pages))
+        .sum()
 }
 
-fn middle_page(pages: Vec<i32>) -> i32 {
+fn middle_page(pages: &[i32]) -> i32 {
     if pages.is_empty() {
         return 0;
     }
@@ -40,33 +89,18 @@ fn middle_page(pages: Vec<i32>) -> i32 {
     pages[middle]
 }
 
-fn correct_order(pages: Vec<i32>, rules: &Rules) -> bool {
-    let mut seen = HashSet::new();
-    let empty = HashSet::new();
-    for page in &pages {
-        let cannot_be = rules.get(&page.to_string()).unwrap_or(&empty);
-        if seen.intersection(cannot_be).count() > 0 {
-            return false;
-        }
-        seen.insert(page.to_string());
-    }
-    true
+pub fn part_2(input: &str) -> Option<i32> {
+    sum_reordered_middles(&Day5::parse(input).ok()?)
 }
 
-fn get_middle_if_correct(input: &str, rules: &Rules) -> Option<i32> {
-    let pages = pages(input);
-    if correct_order(pages.clone(), rules) {
-        Some(middle_page(pages))
-    } else {
-        None
-    }
-}
-
-mod chatgpt;
-
-pub fn part_2(input: &str) -> Option<i32> {
-    let result = chatgpt::solve(input);
-    Some(result)
+/// `None` if the rules for some update form a cycle.
+fn sum_reordered_middles(queue: &PrintQueue) -> Option<i32> {
+    queue
+        .updates
+        .iter()
+        .filter(|pages| !queue.rules.is_ordered(pages))
+        .map(|pages| Some(middle_page(&queue.reorder(pages).ok()?)))
+        .sum()
 }
 
 #[cfg(test)]
@@ -117,10 +151,65 @@ mod tests {
 
     #[test]
     fn test_rules_parsing() {
-        let (part_1, _) = INPUT.split_once("\n\n").unwrap();
-        let rules = rules(part_1);
-        assert_eq!(rules.len(), 6);
-        assert_eq!(rules.get("47").unwrap().len(), 4);
+        let queue = Day5::parse(INPUT).unwrap();
+        assert_eq!(queue.rules().len(), 21);
+        assert_eq!(queue.rules().after(47).count(), 4);
+        assert_eq!(queue.updates().len(), 6);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let result = Day5::parse("47|53\n97-13\n\n75,47");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "line 2, column 3: expected `|`, found `-13`"
+        );
+        let result = Day5::parse("47|53\n\n75,47\n75,x");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "line 4, column 4: expected a number, found `x`"
+        );
+        let result = Day5::parse("47|53");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "expected 2 sections separated by blank lines, found 1"
+        );
+    }
+
+    #[test]
+    fn test_analyze() {
+        let queue = Day5::parse(INPUT).unwrap();
+        let middles = queue.analyze().map(|order| match order {
+            UpdateOrder::Total(order) => order[order.len() / 2],
+            other => panic!("expected a total order, got {:?}", other),
+        });
+        assert_eq!(middles.collect::<Vec<_>>(), [61, 53, 29, 47, 29, 47]);
+    }
+
+    #[test]
+    fn test_reorder() {
+        let queue = Day5::parse("97|75\n75|47\n97|47\n\n75,97,47").unwrap();
+        assert!(!queue.rules().is_ordered(&queue.updates()[0]));
+        assert_eq!(queue.reorder(&queue.updates()[0]), Ok(vec![97, 75, 47]));
+    }
+
+    #[test]
+    fn test_cyclic_rules() {
+        // Pages 10 to 34 each come before the next, and 34 before 10.
+        let rules = (10..35)
+            .map(|page| format!("{}|{}", page, if page == 34 { 10 } else { page + 1 }))
+            .collect::<Vec<_>>();
+        let update = (10..35).rev().map(|page| page.to_string());
+        let input = format!(
+            "{}\n\n{}",
+            rules.join("\n"),
+            update.collect::<Vec<_>>().join(",")
+        );
+        let queue = Day5::parse(&input).unwrap();
+        let cycle = queue.reorder(&queue.updates()[0]).unwrap_err();
+        assert_eq!(cycle.0.len(), 25);
+        assert_eq!(part_2(&input), None);
+        assert_eq!(Day5::part_2(&queue), Answer::Unsolved);
     }
 
     #[test]
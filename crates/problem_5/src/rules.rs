@@ -0,0 +1,137 @@
+use graph::Graph;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// The `X|Y` page ordering rules, indexed from both ends.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageRules {
+    after: HashMap<i32, HashSet<i32>>,
+    before: HashMap<i32, HashSet<i32>>,
+}
+
+impl FromIterator<(i32, i32)> for PageRules {
+    fn from_iter<I: IntoIterator<Item = (i32, i32)>>(rules: I) -> Self {
+        let mut page_rules = Self::default();
+        for (before, after) in rules {
+            page_rules.add(before, after);
+        }
+        page_rules
+    }
+}
+
+impl PageRules {
+    /// Adds the rule `before|after`.
+    pub fn add(&mut self, before: i32, after: i32) {
+        self.after.entry(before).or_default().insert(after);
+        self.before.entry(after).or_default().insert(before);
+    }
+
+    /// The number of rules.
+    pub fn len(&self) -> usize {
+        self.after.values().map(HashSet::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.after.is_empty()
+    }
+
+    /// Whether a rule says `a` must be printed before `b`.
+    pub fn must_precede(&self, a: i32, b: i32) -> bool {
+        self.after.get(&a).is_some_and(|after| after.contains(&b))
+    }
+
+    /// Pages that must be printed after `page`.
+    pub fn after(&self, page: i32) -> impl Iterator<Item = i32> + '_ {
+        self.after.get(&page).into_iter().flatten().copied()
+    }
+
+    /// Pages that must be printed before `page`.
+    pub fn before(&self, page: i32) -> impl Iterator<Item = i32> + '_ {
+        self.before.get(&page).into_iter().flatten().copied()
+    }
+
+    /// Every rule as a `(before, after)` pair.
+    pub fn pairs(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.after
+            .iter()
+            .flat_map(|(&before, after)| after.iter().map(move |&after| (before, after)))
+    }
+
+    /// The rules as a graph with an edge from each page to the pages after
+    /// it. Built from the pairs in sorted order, so the graph, and any cycle
+    /// found in it, is the same from run to run.
+    pub fn graph(&self) -> Graph<i32> {
+        let mut pairs = self.pairs().collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs.into_iter().collect()
+    }
+
+    /// Orders two pages by the rule between them, if there is one.
+    pub fn compare(&self, a: i32, b: i32) -> Ordering {
+        if self.must_precede(a, b) {
+            Ordering::Less
+        } else if self.must_precede(b, a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Whether no page in `update` comes after a page it must precede.
+    pub fn is_ordered(&self, update: &[i32]) -> bool {
+        let mut seen = HashSet::new();
+        update.iter().all(|&page| {
+            let ok = self.after(page).all(|after| !seen.contains(&after));
+            seen.insert(page);
+            ok
+        })
+    }
+
+    /// Sorts `update` with [`PageRules::compare`]. This only gives a valid
+    /// order when the rules relate every pair of pages in the update, as the
+    /// puzzle promises. Pages with no rule between them compare equal, so
+    /// with gaps or cycles in the rules the comparison isn't a total order:
+    /// the result is unspecified and the sort may panic. Use
+    /// [`crate::PrintQueue::reorder`] for those.
+    pub fn sort(&self, update: &mut [i32]) {
+        update.sort_by(|&a, &b| self.compare(a, b));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_rules() {
+        let rules = [(47, 53), (97, 13), (97, 47), (75, 47)]
+            .into_iter()
+            .collect::<PageRules>();
+        assert_eq!(rules.len(), 4);
+        assert!(rules.must_precede(97, 47));
+        assert!(!rules.must_precede(47, 97));
+        let mut before = rules.before(47).collect::<Vec<_>>();
+        before.sort();
+        assert_eq!(before, [75, 97]);
+        assert_eq!(rules.compare(47, 97), Ordering::Greater);
+        assert_eq!(rules.compare(53, 13), Ordering::Equal);
+        let graph = rules.graph();
+        assert_eq!(
+            graph.nodes().copied().collect::<Vec<_>>(),
+            [47, 53, 75, 97, 13]
+        );
+        assert!(graph.has_edge(&97, &13));
+    }
+
+    #[test]
+    fn test_sort() {
+        let rules = [(97, 75), (75, 47), (97, 47)]
+            .into_iter()
+            .collect::<PageRules>();
+        let mut update = [75, 97, 47];
+        assert!(!rules.is_ordered(&update));
+        rules.sort(&mut update);
+        assert_eq!(update, [97, 75, 47]);
+        assert!(rules.is_ordered(&update));
+    }
+}
@@ -1,70 +1,45 @@
+use geometry::{Direction, Point};
+use grid::Grid;
+use solution::{Answer, ParseError, Solution};
 use std::collections::HashSet;
 
-pub fn part_1(input: &str) -> Option<i32> {
-    let world = World::new(input);
-    let guard = world.find_guard();
-    Some(run(&world, &guard).len() as i32)
-}
+pub struct Day6;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct Position {
-    row: usize,
-    col: usize,
-}
+impl Solution for Day6 {
+    type Input = World;
+
+    const DAY: u32 = 6;
+    const TITLE: &'static str = "Guard Gallivant";
 
-impl std::fmt::Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.row, self.col)
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        World::parse(input)
     }
-}
 
-impl Position {
-    fn new(row: usize, col: usize) -> Self {
-        Self { row, col }
+    fn part_1(world: &Self::Input) -> Answer {
+        count_visited(world).into()
     }
-}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+    fn part_2(world: &Self::Input) -> Answer {
+        count_loops(world).into()
+    }
 }
 
-impl TryFrom<char> for Direction {
-    type Error = ();
-
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            '^' => Ok(Direction::Up),
-            'v' => Ok(Direction::Down),
-            '<' => Ok(Direction::Left),
-            '>' => Ok(Direction::Right),
-            _ => Err(()),
-        }
-    }
+pub fn part_1(input: &str) -> Option<i32> {
+    Some(count_visited(&World::parse(input).ok()?))
 }
 
-impl Direction {
-    fn turn_right(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
+fn count_visited(world: &World) -> i32 {
+    run(world, &world.guard).len() as i32
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct Guard {
-    position: Position,
+    position: Point,
     direction: Direction,
 }
 
 impl Guard {
-    fn new(position: Position, direction: Direction) -> Self {
+    fn new(position: Point, direction: Direction) -> Self {
         Self {
             position,
             direction,
@@ -73,44 +48,13 @@ impl Guard {
 
     /// Returns the position of the space the guard is looking at,
     /// otherwise None if the guard is looking at the edge of the world.
-    fn peek(&self, grid: &[Vec<char>]) -> Option<(char, Position)> {
-        let row = self.position.row;
-        let col = self.position.col;
-
-        match self.direction {
-            Direction::Up => {
-                if row == 0 {
-                    None
-                } else {
-                    Some((grid[row - 1][col], Position::new(row - 1, col)))
-                }
-            }
-            Direction::Down => {
-                if row == grid.len() - 1 {
-                    None
-                } else {
-                    Some((grid[row + 1][col], Position::new(row + 1, col)))
-                }
-            }
-            Direction::Left => {
-                if col == 0 {
-                    None
-                } else {
-                    Some((grid[row][col - 1], Position::new(row, col - 1)))
-                }
-            }
-            Direction::Right => {
-                if col == grid[0].len() - 1 {
-                    None
-                } else {
-                    Some((grid[row][col + 1], Position::new(row, col + 1)))
-                }
-            }
-        }
+    fn peek(&self, grid: &Grid<char>) -> Option<(char, Point)> {
+        let position = self.position + self.direction.offset();
+        grid.get_point(position).map(|&c| (c, position))
     }
 
     /// Returns the next guard position, otherwise None if the guard is looking at the edge of the world.
-    fn next(&self, grid: &[Vec<char>]) -> Option<Self> {
+    fn next(&self, grid: &Grid<char>) -> Option<Self> {
         let peek = self.peek(grid);
         match peek {
             Some(('#', _)) => {
@@ -124,32 +68,24 @@ impl Guard {
 }
 
 #[derive(Debug, Clone)]
-struct World {
-    grid: Vec<Vec<char>>,
+pub struct World {
+    grid: Grid<char>,
+    guard: Guard,
 }
 
 impl World {
-    fn new(input: &str) -> Self {
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        Self { grid }
-    }
-
-    fn find_guard(&self) -> Guard {
-        for (row, line) in self.grid.iter().enumerate() {
-            for (col, &cell) in line.iter().enumerate() {
-                if let Ok(direction) = Direction::try_from(cell) {
-                    let position = Position::new(row, col);
-                    return Guard::new(position, direction);
-                }
-            }
-        }
-        panic!("guard not found");
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(input)?;
+        let guard =
+            find_guard(&grid).ok_or_else(|| ParseError::new("guard not found in the map"))?;
+        Ok(Self { grid, guard })
     }
 
-    fn with_distraction(&self, distraction: Position) -> Option<Self> {
+    fn with_distraction(&self, distraction: Point) -> Option<Self> {
         let mut world = self.clone();
-        if world.grid[distraction.row][distraction.col] == '.' {
-            world.grid[distraction.row][distraction.col] = '#';
+        let cell = world.grid.get_point_mut(distraction)?;
+        if *cell == '.' {
+            *cell = '#';
             Some(world)
         } else {
             None
@@ -157,7 +93,13 @@ impl World {
     }
 }
 
-fn run(world: &World, guard: &Guard) -> HashSet<Position> {
+fn find_guard(grid: &Grid<char>) -> Option<Guard> {
+    grid.iter().find_map(|(pos, &cell)| {
+        Direction::from_arrow(cell).map(|direction| Guard::new(Point::from_pos(pos), direction))
+    })
+}
+
+fn run(world: &World, guard: &Guard) -> HashSet<Point> {
     let mut path = HashSet::new();
     path.insert(guard.position);
     let mut guard = *guard;
@@ -195,9 +137,12 @@ fn loops(world: &World, guard: &Guard) -> bool {
 }
 
 pub fn part_2(input: &str) -> Option<i32> {
-    let world = World::new(input);
-    let guard = world.find_guard();
-    let guard_places = run(&world, &guard);
+    Some(count_loops(&World::parse(input).ok()?))
+}
+
+fn count_loops(world: &World) -> i32 {
+    let guard = world.guard;
+    let guard_places = run(world, &guard);
 
     let mut guard_loops = 0;
 
@@ -209,7 +154,7 @@ pub fn part_2(input: &str) -> Option<i32> {
             }
         }
     }
-    Some(guard_loops)
+    guard_loops
 }
 
 #[cfg(test)]
@@ -233,6 +178,15 @@ mod tests {
         assert_eq!(result, Some(41));
     }
 
+    #[test]
+    fn test_missing_guard() {
+        let result = Day6::parse("....\n.#..");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "guard not found in the map"
+        );
+    }
+
     #[test]
     fn test_part_2() {
         let result = part_2(INPUT);
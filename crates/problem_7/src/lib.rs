@@ -1,59 +1,226 @@
-mod sequence;
-
-pub fn part_1(input: &str) -> Option<i64> {
-    let mut total = 0;
-    for line in input.lines() {
-        let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
-        let depth = parts[0].strip_suffix(":").unwrap().parse::<i64>().unwrap();
-        let nums = parts[1..]
+use parsing::key_values;
+use solution::{Answer, ParseError, Solution};
+use std::fmt;
+use std::ops::ControlFlow;
+
+pub use ops::{Add, Concat, Inverse, Mul, Operator, OperatorSet, Sub, Undo, Xor};
+
+mod ops;
+
+pub struct Day7;
+
+pub struct Equation {
+    total: u64,
+    nums: Vec<u64>,
+}
+
+impl Solution for Day7 {
+    type Input = Vec<Equation>;
+
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "Bridge Repair";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let equations = parsing::lines(input, key_values)?;
+        Ok(equations
+            .into_iter()
+            .map(|(total, nums)| Equation::new(total, nums))
+            .collect())
+    }
+
+    fn part_1(equations: &Self::Input) -> Answer {
+        calibration_total(equations, &OperatorSet::part_1()).into()
+    }
+
+    fn part_2(equations: &Self::Input) -> Answer {
+        calibration_total(equations, &OperatorSet::part_2()).into()
+    }
+}
+
+fn calibration_total(equations: &[Equation], ops: &OperatorSet) -> u64 {
+    equations
+        .iter()
+        .filter(|eq| eq.is_solvable(ops))
+        .map(|eq| eq.total)
+        .sum()
+}
+
+pub fn part_1(input: &str) -> Option<u64> {
+    Some(calibration_total(
+        &Day7::parse(input).ok()?,
+        &OperatorSet::part_1(),
+    ))
+}
+
+/// An equation with its operators filled in.
+#[derive(Debug, Clone)]
+pub struct Expression<'a> {
+    pub total: u64,
+    pub nums: Vec<u64>,
+    pub ops: Vec<&'a dyn Operator>,
+}
+
+impl Expression<'_> {
+    /// Evaluates left to right, or `None` on overflow.
+    pub fn evaluate(&self) -> Option<u64> {
+        let (&first, rest) = self.nums.split_first()?;
+        self.ops
             .iter()
-            .map(|&s| s.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
-        if has_valid_expression(&[Ops::Add, Ops::Mul], &nums, depth) {
-            total += depth;
+            .zip(rest)
+            .try_fold(first, |lhs, (op, &rhs)| op.apply(lhs, rhs))
+    }
+}
+
+/// Renders like `3267 = 81 * 40 + 27`.
+impl fmt::Display for Expression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} =", self.total)?;
+        for (i, num) in self.nums.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {}", self.ops[i - 1])?;
+            }
+            write!(f, " {}", num)?;
         }
+        Ok(())
     }
-    Some(total)
 }
 
-#[derive(Clone, Copy)]
-enum Ops {
-    Add,
-    Mul,
-    Concat,
+type Visit<'v, 'a, B> = dyn FnMut(&[&'a dyn Operator]) -> ControlFlow<B> + 'v;
+
+impl Equation {
+    pub fn new(total: u64, nums: Vec<u64>) -> Self {
+        Self { total, nums }
+    }
+
+    pub fn is_solvable(&self, ops: &OperatorSet) -> bool {
+        self.search(ops, &mut |_| ControlFlow::Break(())).is_break()
+    }
+
+    /// The first assignment of `ops` that makes the equation true.
+    pub fn solve<'a>(&self, ops: &'a OperatorSet) -> Option<Expression<'a>> {
+        self.search(ops, &mut |assignment| {
+            ControlFlow::Break(self.expression(assignment))
+        })
+        .break_value()
+    }
+
+    /// How many assignments of `ops` make the equation true.
+    pub fn count_solutions(&self, ops: &OperatorSet) -> usize {
+        let mut count = 0;
+        let _ = self.search(ops, &mut |_| {
+            count += 1;
+            ControlFlow::<()>::Continue(())
+        });
+        count
+    }
+
+    /// Every assignment of `ops` that makes the equation true.
+    pub fn solutions<'a>(&self, ops: &'a OperatorSet) -> Vec<Expression<'a>> {
+        let mut solutions = Vec::new();
+        let _ = self.search(ops, &mut |assignment| {
+            solutions.push(self.expression(assignment));
+            ControlFlow::<()>::Continue(())
+        });
+        solutions
+    }
+
+    fn expression<'a>(&self, ops: &[&'a dyn Operator]) -> Expression<'a> {
+        Expression {
+            total: self.total,
+            nums: self.nums.clone(),
+            ops: ops.to_vec(),
+        }
+    }
+
+    fn search<'a, B>(&self, ops: &'a OperatorSet, visit: &mut Visit<'_, 'a, B>) -> ControlFlow<B> {
+        let invertible = ops.is_invertible();
+        let ops = ops.iter().collect::<Vec<_>>();
+        if invertible {
+            return backward(&ops, &self.nums, self.total, &mut Vec::new(), visit);
+        }
+        let Some((&first, rest)) = self.nums.split_first() else {
+            return ControlFlow::Continue(());
+        };
+        forward(&ops, rest, first, Some(self.total), &mut Vec::new(), visit)
+    }
 }
 
-fn has_valid_expression(ops: &[Ops], nums: &[i64], total: i64) -> bool {
-    let n = nums.len();
-    sequence::SequenceIterator::new(ops, n - 1).any(|ops| {
-        let mut result = nums[0];
-        for (i, &op) in ops.iter().enumerate() {
-            match op {
-                Ops::Add => result += nums[i + 1],
-                Ops::Mul => result *= nums[i + 1],
-                Ops::Concat => {
-                    result = format!("{}{}", result, nums[i + 1]).parse::<i64>().unwrap()
-                }
+/// Calls `visit` with every choice of `ops` between `nums` that gives
+/// `total` when evaluated left to right. Works backwards from the total,
+/// undoing the last operation each step, so most branches die on the first
+/// number: the total rarely divides evenly or ends in the right digits.
+/// `undone` holds the operators chosen so far, last one first.
+fn backward<'a, B>(
+    ops: &[&'a dyn Operator],
+    nums: &[u64],
+    total: u64,
+    undone: &mut Vec<&'a dyn Operator>,
+    visit: &mut Visit<'_, 'a, B>,
+) -> ControlFlow<B> {
+    let Some((&last, rest)) = nums.split_last() else {
+        return ControlFlow::Continue(());
+    };
+    let Some((&first, middle)) = rest.split_first() else {
+        if total == last {
+            let assignment = undone.iter().rev().copied().collect::<Vec<_>>();
+            return visit(&assignment);
+        }
+        return ControlFlow::Continue(());
+    };
+    for &op in ops {
+        let inverse = op.inverse().expect("backward search needs inverses");
+        match inverse.undo(total, last) {
+            Undo::Nothing => {}
+            Undo::Exactly(lhs) => {
+                undone.push(op);
+                backward(ops, rest, lhs, undone, visit)?;
+                undone.pop();
+            }
+            // There's nothing to undo, so every way of getting this far works.
+            Undo::Anything => {
+                forward(ops, middle, first, None, &mut Vec::new(), &mut |prefix| {
+                    let mut assignment = prefix.to_vec();
+                    assignment.push(op);
+                    assignment.extend(undone.iter().rev());
+                    visit(&assignment)
+                })?;
             }
         }
-        result == total
-    })
+    }
+    ControlFlow::Continue(())
 }
 
-pub fn part_2(input: &str) -> Option<i64> {
-    let mut total = 0;
-    for line in input.lines() {
-        let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
-        let depth = parts[0].strip_suffix(":").unwrap().parse::<i64>().unwrap();
-        let nums = parts[1..]
-            .iter()
-            .map(|&s| s.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
-        if has_valid_expression(&[Ops::Add, Ops::Mul, Ops::Concat], &nums, depth) {
-            total += depth;
+/// Calls `visit` with every choice of `ops` between `value` and `nums`
+/// that evaluates without overflow, and to `total` if one is given.
+fn forward<'a, B>(
+    ops: &[&'a dyn Operator],
+    nums: &[u64],
+    value: u64,
+    total: Option<u64>,
+    chosen: &mut Vec<&'a dyn Operator>,
+    visit: &mut Visit<'_, 'a, B>,
+) -> ControlFlow<B> {
+    let Some((&next, rest)) = nums.split_first() else {
+        if total.is_none_or(|total| total == value) {
+            return visit(chosen);
+        }
+        return ControlFlow::Continue(());
+    };
+    for &op in ops {
+        if let Some(value) = op.apply(value, next) {
+            chosen.push(op);
+            forward(ops, rest, value, total, chosen, visit)?;
+            chosen.pop();
         }
     }
-    Some(total)
+    ControlFlow::Continue(())
+}
+
+pub fn part_2(input: &str) -> Option<u64> {
+    Some(calibration_total(
+        &Day7::parse(input).ok()?,
+        &OperatorSet::part_2(),
+    ))
 }
 
 #[cfg(test)]
@@ -76,6 +243,116 @@ mod tests {
         assert_eq!(result, Some(3749));
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let result = Day7::parse("190: 10 19\n3267 81 40");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "line 2, column 5: expected `:`, found whitespace"
+        );
+        let result = Day7::parse("190: 10 1x");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "line 1, column 9: expected a number, found `1x`"
+        );
+        let result = Day7::parse("190:");
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "line 1, column 5: expected a number, found end of line"
+        );
+    }
+
+    /// Counts the operator sequences that work by trying every one forwards.
+    fn brute_force(ops: &OperatorSet, nums: &[u64], total: u64) -> usize {
+        let ops = ops.iter().collect::<Vec<_>>();
+        combinatorics::cartesian_power(&ops, nums.len() - 1)
+            .filter(|seq| {
+                let expression = Expression {
+                    total,
+                    nums: nums.to_vec(),
+                    ops: seq.clone(),
+                };
+                expression.evaluate() == Some(total)
+            })
+            .count()
+    }
+
+    /// Keeps the larger operand; has no inverse, so forces a forward search.
+    #[derive(Debug)]
+    struct Max;
+
+    impl fmt::Display for Max {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "max")
+        }
+    }
+
+    impl Operator for Max {
+        fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+            Some(lhs.max(rhs))
+        }
+    }
+
+    #[test]
+    fn test_search_matches_brute_force() {
+        let sets = [
+            OperatorSet::part_2(),
+            "+ - ^ ||2".parse().unwrap(),
+            OperatorSet::part_1().with(Max),
+        ];
+        for ops in &sets {
+            for eq in Day7::parse(INPUT).unwrap() {
+                for total in [eq.total, eq.total + 1, 0, 19] {
+                    let eq = Equation::new(total, eq.nums.clone());
+                    assert_eq!(
+                        eq.count_solutions(ops),
+                        brute_force(ops, &eq.nums, total),
+                        "{} with {:?} and {}",
+                        total,
+                        eq.nums,
+                        ops
+                    );
+                }
+            }
+        }
+        let ops = OperatorSet::part_2();
+        let eq = Equation::new(0, vec![3, 4, 0]);
+        assert_eq!(eq.count_solutions(&ops), brute_force(&ops, &eq.nums, 0));
+        assert!(Equation::new(5, vec![0, 5]).is_solvable(&ops));
+        assert!(Equation::new(100, vec![10, 0]).is_solvable(&ops));
+    }
+
+    #[test]
+    fn test_witness() {
+        let ops = OperatorSet::part_2();
+        let eq = Equation::new(3267, vec![81, 40, 27]);
+        let witness = eq.solve(&ops).unwrap();
+        assert_eq!(witness.to_string(), "3267 = 81 * 40 + 27");
+        assert_eq!(witness.evaluate(), Some(3267));
+        let solutions = eq.solutions(&ops);
+        assert_eq!(solutions.len(), 2);
+        assert_eq!(solutions[1].to_string(), "3267 = 81 + 40 * 27");
+        let eq = Equation::new(156, vec![15, 6]);
+        assert_eq!(eq.solve(&ops).unwrap().to_string(), "156 = 15 || 6");
+        assert!(eq.solve(&OperatorSet::part_1()).is_none());
+        let ops = OperatorSet::part_1().with(Max);
+        let eq = Equation::new(25, vec![3, 5, 5]);
+        assert_eq!(eq.solve(&ops).unwrap().to_string(), "25 = 3 max 5 * 5");
+    }
+
+    #[test]
+    fn test_overflow() {
+        let ops = OperatorSet::part_2();
+        assert!(!Equation::new(1, vec![u64::MAX, 2]).is_solvable(&ops));
+        let nums = [999_999_999; 20];
+        assert!(!Equation::new(7, nums.to_vec()).is_solvable(&ops));
+        assert_eq!(brute_force(&OperatorSet::part_1(), &nums[..8], 7), 0);
+        // The prefix before `* 0` still has to fit.
+        let eq = Equation::new(0, vec![u64::MAX, 0, 0]);
+        assert_eq!(eq.count_solutions(&ops), 4);
+        assert_eq!(brute_force(&ops, &eq.nums, 0), 4);
+    }
+
     #[test]
     fn test_part_2() {
         let result = part_2(INPUT);
@@ -0,0 +1,310 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A binary operator that can appear between the numbers of an equation.
+/// Its `Display` is the symbol used when printing expressions.
+pub trait Operator: fmt::Debug + fmt::Display + Sync {
+    /// `lhs op rhs`, or `None` if the result doesn't fit.
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64>;
+
+    /// How to undo this operator, if it can be. The solver searches
+    /// backwards from the total when every operator in the set has one, and
+    /// falls back to trying every sequence forwards otherwise.
+    fn inverse(&self) -> Option<&dyn Inverse> {
+        None
+    }
+}
+
+pub trait Inverse {
+    /// The left operands for which `lhs op rhs == result`.
+    fn undo(&self, result: u64, rhs: u64) -> Undo;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Undo {
+    /// No left operand works.
+    Nothing,
+    /// Exactly this one does.
+    Exactly(u64),
+    /// Every left operand does, like for `x * 0 == 0`.
+    Anything,
+}
+
+impl From<Option<u64>> for Undo {
+    fn from(lhs: Option<u64>) -> Self {
+        lhs.map_or(Undo::Nothing, Undo::Exactly)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Add;
+
+impl fmt::Display for Add {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+")
+    }
+}
+
+impl Operator for Add {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.checked_add(rhs)
+    }
+
+    fn inverse(&self) -> Option<&dyn Inverse> {
+        Some(self)
+    }
+}
+
+impl Inverse for Add {
+    fn undo(&self, result: u64, rhs: u64) -> Undo {
+        result.checked_sub(rhs).into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mul;
+
+impl fmt::Display for Mul {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "*")
+    }
+}
+
+impl Operator for Mul {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.checked_mul(rhs)
+    }
+
+    fn inverse(&self) -> Option<&dyn Inverse> {
+        Some(self)
+    }
+}
+
+impl Inverse for Mul {
+    fn undo(&self, result: u64, rhs: u64) -> Undo {
+        match (result, rhs) {
+            (0, 0) => Undo::Anything,
+            (_, 0) => Undo::Nothing,
+            _ => result.is_multiple_of(rhs).then(|| result / rhs).into(),
+        }
+    }
+}
+
+/// Writes the digits of `rhs` after those of `lhs`, in the given base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Concat {
+    base: u64,
+}
+
+impl Concat {
+    pub const DECIMAL: Self = Self { base: 10 };
+
+    pub fn new(base: u64) -> Self {
+        assert!(base >= 2, "concatenation needs a base of at least 2");
+        Self { base }
+    }
+
+    /// The power of the base that moves a number left past all of `rhs`'s
+    /// digits, or `None` if that doesn't fit.
+    fn shift(&self, rhs: u64) -> Option<u64> {
+        let mut shift = self.base;
+        while shift <= rhs {
+            shift = shift.checked_mul(self.base)?;
+        }
+        Some(shift)
+    }
+}
+
+impl fmt::Display for Concat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.base {
+            10 => write!(f, "||"),
+            base => write!(f, "||{}", base),
+        }
+    }
+}
+
+impl Operator for Concat {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self.shift(rhs) {
+            Some(shift) => lhs.checked_mul(shift)?.checked_add(rhs),
+            None => (lhs == 0).then_some(rhs),
+        }
+    }
+
+    fn inverse(&self) -> Option<&dyn Inverse> {
+        Some(self)
+    }
+}
+
+impl Inverse for Concat {
+    fn undo(&self, result: u64, rhs: u64) -> Undo {
+        match self.shift(rhs) {
+            Some(shift) => (result % shift == rhs).then(|| result / shift).into(),
+            None => (result == rhs).then_some(0).into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sub;
+
+impl fmt::Display for Sub {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "-")
+    }
+}
+
+impl Operator for Sub {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.checked_sub(rhs)
+    }
+
+    fn inverse(&self) -> Option<&dyn Inverse> {
+        Some(self)
+    }
+}
+
+impl Inverse for Sub {
+    fn undo(&self, result: u64, rhs: u64) -> Undo {
+        result.checked_add(rhs).into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xor;
+
+impl fmt::Display for Xor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "^")
+    }
+}
+
+impl Operator for Xor {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        Some(lhs ^ rhs)
+    }
+
+    fn inverse(&self) -> Option<&dyn Inverse> {
+        Some(self)
+    }
+}
+
+impl Inverse for Xor {
+    fn undo(&self, result: u64, rhs: u64) -> Undo {
+        Undo::Exactly(result ^ rhs)
+    }
+}
+
+/// The operators a solver may choose from.
+#[derive(Debug)]
+pub struct OperatorSet(Vec<Box<dyn Operator>>);
+
+impl OperatorSet {
+    pub fn new(ops: Vec<Box<dyn Operator>>) -> Self {
+        Self(ops)
+    }
+
+    pub fn part_1() -> Self {
+        Self(vec![Box::new(Add), Box::new(Mul)])
+    }
+
+    pub fn part_2() -> Self {
+        Self(vec![
+            Box::new(Add),
+            Box::new(Mul),
+            Box::new(Concat::DECIMAL),
+        ])
+    }
+
+    pub fn with(mut self, op: impl Operator + 'static) -> Self {
+        self.0.push(Box::new(op));
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Operator> {
+        self.0.iter().map(|op| op.as_ref())
+    }
+
+    pub fn is_invertible(&self) -> bool {
+        self.iter().all(|op| op.inverse().is_some())
+    }
+}
+
+impl fmt::Display for OperatorSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, op) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", op)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses whitespace-separated symbols, like `+ * ||`. `||` concatenates
+/// in base 10 and `||k` in base `k`.
+impl FromStr for OperatorSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ops = s
+            .split_whitespace()
+            .map(|symbol| -> Result<Box<dyn Operator>, String> {
+                Ok(match symbol {
+                    "+" => Box::new(Add),
+                    "*" => Box::new(Mul),
+                    "-" => Box::new(Sub),
+                    "^" => Box::new(Xor),
+                    "||" => Box::new(Concat::DECIMAL),
+                    _ => match symbol.strip_prefix("||").map(str::parse) {
+                        Some(Ok(base)) if base >= 2 => Box::new(Concat::new(base)),
+                        _ => return Err(format!("unknown operator `{}`", symbol)),
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(ops))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat() {
+        assert_eq!(Concat::DECIMAL.apply(12, 345), Some(12345));
+        assert_eq!(Concat::DECIMAL.apply(12, 0), Some(120));
+        assert_eq!(Concat::DECIMAL.apply(u64::MAX / 10, 99), None);
+        assert_eq!(Concat::DECIMAL.undo(12345, 345), Undo::Exactly(12));
+        assert_eq!(Concat::DECIMAL.undo(12345, 45), Undo::Exactly(123));
+        assert_eq!(Concat::DECIMAL.undo(12345, 44), Undo::Nothing);
+        assert_eq!(Concat::new(2).apply(0b101, 0b11), Some(0b10111));
+        assert_eq!(Concat::DECIMAL.apply(0, u64::MAX), Some(u64::MAX));
+        assert_eq!(Concat::DECIMAL.undo(u64::MAX, u64::MAX), Undo::Exactly(0));
+    }
+
+    #[test]
+    fn test_inverses() {
+        assert_eq!(Add.undo(5, 7), Undo::Nothing);
+        assert_eq!(Mul.undo(0, 0), Undo::Anything);
+        assert_eq!(Mul.undo(12, 5), Undo::Nothing);
+        assert_eq!(Sub.undo(3, 4), Undo::Exactly(7));
+        assert_eq!(Xor.undo(0b110, 0b011), Undo::Exactly(0b101));
+    }
+
+    #[test]
+    fn test_operator_set() {
+        assert_eq!(OperatorSet::part_2().to_string(), "+ * ||");
+        let set = "+ - ^ ||2".parse::<OperatorSet>().unwrap();
+        assert_eq!(set.to_string(), "+ - ^ ||2");
+        assert!(set.is_invertible());
+        assert_eq!(
+            "+ / ||".parse::<OperatorSet>().unwrap_err(),
+            "unknown operator `/`"
+        );
+        assert!("||1".parse::<OperatorSet>().is_err());
+    }
+}
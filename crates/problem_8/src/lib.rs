@@ -1,109 +1,94 @@
+use combinatorics::pairs;
+use geometry::Point;
+use grid::Grid;
+use solution::{Answer, ParseError, Solution};
 use std::collections::{HashMap, HashSet};
 
-mod coord;
+pub struct Day8;
 
-use coord::Position;
+pub struct Antennas {
+    grid: Grid<char>,
+    char_locs: HashMap<char, Vec<Point>>,
+}
 
-pub fn part_1(input: &str) -> Option<i32> {
-    let mut cols = 0;
-    let mut rows = 0;
-    let mut char_locs = HashMap::new();
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            if row > rows {
-                rows = row;
-            }
-            if col > cols {
-                cols = col;
-            }
+impl Antennas {
+    fn contains(&self, point: Point) -> bool {
+        self.grid.contains_point(point)
+    }
+}
+
+/// The point as far beyond `b` as `b` is from `a`.
+fn antinode_from(a: Point, b: Point) -> Point {
+    b + (b - a)
+}
+
+impl Solution for Day8 {
+    type Input = Antennas;
+
+    const DAY: u32 = 8;
+    const TITLE: &'static str = "Resonant Collinearity";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let grid = Grid::parse(input)?;
+        let mut char_locs = HashMap::new();
+        for (pos, &c) in grid.iter() {
             if c != '.' {
                 char_locs
                     .entry(c)
                     .or_insert_with(Vec::new)
-                    .push(Position::new(row as i64, col as i64));
+                    .push(Point::from_pos(pos));
             }
         }
+        Ok(Antennas { grid, char_locs })
     }
-    // println!("map dimensions: {} {}", rows, cols);
-    // println!("character locations: {:?}", char_locs);
 
+    fn part_1(antennas: &Self::Input) -> Answer {
+        count_antinodes(antennas).into()
+    }
+
+    fn part_2(antennas: &Self::Input) -> Answer {
+        count_harmonic_antinodes(antennas).into()
+    }
+}
+
+pub fn part_1(input: &str) -> Option<i32> {
+    Some(count_antinodes(&Day8::parse(input).ok()?))
+}
+
+fn count_antinodes(antennas: &Antennas) -> i32 {
     let mut antinodes = HashSet::new();
-    for locs in char_locs.values() {
-        for i in 0..locs.len() {
-            for j in 0..locs.len() {
-                if i == j {
-                    continue;
-                }
-                let antinode = locs[j].antinode(locs[i]);
-                if antinode.row >= 0
-                    && antinode.row <= rows as i64
-                    && antinode.col >= 0
-                    && antinode.col <= cols as i64
-                {
-                    antinodes.insert(antinode);
-                }
-                let antinode = locs[i].antinode(locs[j]);
-                if antinode.row >= 0
-                    && antinode.row <= rows as i64
-                    && antinode.col >= 0
-                    && antinode.col <= cols as i64
-                {
+    for locs in antennas.char_locs.values() {
+        for (&a, &b) in pairs(locs) {
+            for antinode in [antinode_from(a, b), antinode_from(b, a)] {
+                if antennas.contains(antinode) {
                     antinodes.insert(antinode);
                 }
             }
         }
     }
-    // println!("antinodes: {:?}", antinodes);
-
-    Some(antinodes.len() as i32)
+    antinodes.len() as i32
 }
 
 pub fn part_2(input: &str) -> Option<i32> {
-    let mut cols = 0;
-    let mut rows = 0;
-    let mut char_locs = HashMap::new();
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            if row > rows {
-                rows = row;
-            }
-            if col > cols {
-                cols = col;
-            }
-            if c != '.' {
-                char_locs
-                    .entry(c)
-                    .or_insert_with(Vec::new)
-                    .push(Position::new(row as i64, col as i64));
-            }
-        }
-    }
-    // println!("map dimensions: {} {}", rows, cols);
-    // println!("character locations: {:?}", char_locs);
+    Some(count_harmonic_antinodes(&Day8::parse(input).ok()?))
+}
 
+fn count_harmonic_antinodes(antennas: &Antennas) -> i32 {
     let mut antinodes = HashSet::new();
-    for locs in char_locs.values() {
-        for i in 0..locs.len() {
-            for j in 0..locs.len() {
-                if i == j {
-                    continue;
-                }
-                for k in -50..=50 {
-                    let antinode = locs[j] + (locs[j] - locs[i]).scale(k);
-                    if antinode.row >= 0
-                        && antinode.row <= rows as i64
-                        && antinode.col >= 0
-                        && antinode.col <= cols as i64
-                    {
-                        antinodes.insert(antinode);
-                    }
+    for locs in antennas.char_locs.values() {
+        for (&a, &b) in pairs(locs) {
+            // Step out from each antenna, away from the other, until the
+            // line leaves the grid.
+            for (start, step) in [(b, b - a), (a, a - b)] {
+                let mut antinode = start;
+                while antennas.contains(antinode) {
+                    antinodes.insert(antinode);
+                    antinode += step;
                 }
             }
         }
     }
-    // println!("antinodes: {:?}", antinodes);
-
-    Some(antinodes.len() as i32)
+    antinodes.len() as i32
 }
 
 #[cfg(test)]
@@ -123,6 +108,12 @@ mod tests {
 ............
 ............";
 
+    #[test]
+    fn test_antinode() {
+        let result = antinode_from(Point::new(1, 1), Point::new(2, 2));
+        assert_eq!(result, Point::new(3, 3));
+    }
+
     #[test]
     fn test_part_1() {
         let result = part_1(INPUT);
@@ -134,4 +125,10 @@ mod tests {
         let result = part_2(INPUT);
         assert_eq!(result, Some(34));
     }
+
+    #[test]
+    fn test_long_lines() {
+        let input = format!("AA{}", ".".repeat(118));
+        assert_eq!(part_2(&input), Some(120));
+    }
 }
@@ -0,0 +1,337 @@
+use crate::strategy::{Blocks, FirstFit, Strategy};
+use std::fmt;
+
+/// A run of consecutive blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(offset: usize, len: usize) -> Self {
+        Self { offset, len }
+    }
+
+    /// The offset just past the last block.
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+/// A span of blocks holding a file, or part of one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extent {
+    pub id: u32,
+    pub span: Span,
+}
+
+impl Extent {
+    pub fn new(id: u32, offset: usize, len: usize) -> Self {
+        Self {
+            id,
+            span: Span::new(offset, len),
+        }
+    }
+
+    /// The sum of `position * id` over the extent's blocks.
+    pub fn checksum(&self) -> u64 {
+        let Span { offset, len } = self.span;
+        let positions = (len * offset + len * len.saturating_sub(1) / 2) as u64;
+        positions * self.id as u64
+    }
+}
+
+/// Blocks of a file moved from one span to another of the same length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub id: u32,
+    pub from: Span,
+    pub to: Span,
+}
+
+impl Move {
+    /// Replays the move on a block-by-block copy of the disk.
+    pub fn apply(&self, blocks: &mut [Option<u32>]) {
+        blocks[self.from.offset..self.from.end()].fill(None);
+        blocks[self.to.offset..self.to.end()].fill(Some(self.id));
+    }
+}
+
+/// Renders like `file 9: 40..42 -> 2..4`.
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "file {}: {}..{} -> {}..{}",
+            self.id,
+            self.from.offset,
+            self.from.end(),
+            self.to.offset,
+            self.to.end()
+        )
+    }
+}
+
+/// A disk as the spans its files occupy, so nothing has to walk it block by
+/// block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disk {
+    /// Sorted by offset.
+    extents: Vec<Extent>,
+    len: usize,
+}
+
+impl Disk {
+    /// Lays out a dense disk map: alternating file and free lengths, with
+    /// files numbered from 0.
+    pub fn from_map(map: &[u32]) -> Self {
+        let mut extents = Vec::new();
+        let mut offset = 0;
+        for (i, &len) in map.iter().enumerate() {
+            let len = len as usize;
+            if i % 2 == 0 && len > 0 {
+                extents.push(Extent::new((i / 2) as u32, offset, len));
+            }
+            offset += len;
+        }
+        Self::from_extents(extents, offset)
+    }
+
+    /// `extents` must not overlap or run past `len`.
+    pub(crate) fn from_extents(mut extents: Vec<Extent>, len: usize) -> Self {
+        extents.sort_by_key(|extent| extent.span.offset);
+        Self { extents, len }
+    }
+
+    /// The number of blocks, used or free.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The file extents, in disk order.
+    pub fn extents(&self) -> &[Extent] {
+        &self.extents
+    }
+
+    /// The free spans between and after the extents, in disk order.
+    pub fn gaps(&self) -> Vec<Span> {
+        let mut gaps = Vec::new();
+        let mut offset = 0;
+        let ends = self.extents.iter().map(|extent| extent.span);
+        for span in ends.chain([Span::new(self.len, 0)]) {
+            if span.offset > offset {
+                gaps.push(Span::new(offset, span.offset - offset));
+            }
+            offset = span.end();
+        }
+        gaps
+    }
+
+    /// The file id in each block, or `None` where it's free.
+    pub fn blocks(&self) -> Vec<Option<u32>> {
+        let mut blocks = vec![None; self.len];
+        for extent in &self.extents {
+            let Span { offset, len } = extent.span;
+            blocks[offset..offset + len].fill(Some(extent.id));
+        }
+        blocks
+    }
+
+    pub fn checksum(&self) -> u64 {
+        self.extents.iter().map(Extent::checksum).sum()
+    }
+
+    /// External fragmentation: the share of free blocks outside the largest
+    /// gap. 0 when the free space is one span, approaching 1 as it scatters.
+    pub fn fragmentation(&self) -> f64 {
+        let gaps = self.gaps();
+        let free = gaps.iter().map(|gap| gap.len).sum::<usize>();
+        let largest = gaps.iter().map(|gap| gap.len).max().unwrap_or(0);
+        if free == 0 {
+            return 0.0;
+        }
+        1.0 - largest as f64 / free as f64
+    }
+
+    /// Moves blocks one at a time from the end of the disk into the leftmost
+    /// free block, until there are no gaps between files.
+    pub fn compact_blocks(&mut self) {
+        self.compact_blocks_with(|_| {});
+    }
+
+    /// Like [`Disk::compact_blocks`], calling `observe` with each move.
+    /// Consecutive blocks that go to the same gap move together.
+    pub fn compact_blocks_with(&mut self, mut observe: impl FnMut(Move)) {
+        Blocks.compact(self, &mut observe);
+    }
+
+    /// Tries each file once, highest id first, and moves it whole into the
+    /// leftmost gap before it that fits.
+    pub fn compact_files(&mut self) {
+        self.compact_files_with(|_| {});
+    }
+
+    /// Like [`Disk::compact_files`], calling `observe` with each move.
+    pub fn compact_files_with(&mut self, mut observe: impl FnMut(Move)) {
+        FirstFit.compact(self, &mut observe);
+    }
+
+    /// Swaps in the extents a strategy ended up with.
+    pub(crate) fn set_extents(&mut self, extents: Vec<Extent>) {
+        *self = Self::from_extents(extents, self.len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Moves single blocks from the end into the leftmost free block.
+    fn naive_compact_blocks(blocks: &mut [Option<u32>]) {
+        let (mut left, mut right) = (0, blocks.len());
+        while left < right {
+            if blocks[left].is_some() {
+                left += 1;
+            } else if blocks[right - 1].is_none() {
+                right -= 1;
+            } else {
+                blocks.swap(left, right - 1);
+            }
+        }
+    }
+
+    /// Moves whole files, highest id first, by scanning for free runs.
+    fn naive_compact_files(blocks: &mut [Option<u32>]) {
+        let max_id = blocks.iter().flatten().max().copied().unwrap_or(0);
+        for id in (0..=max_id).rev() {
+            let Some(start) = blocks.iter().position(|&b| b == Some(id)) else {
+                continue;
+            };
+            let len = blocks[start..]
+                .iter()
+                .take_while(|&&b| b == Some(id))
+                .count();
+            let free = (0..start).find(|&i| blocks[i..i + len].iter().all(Option::is_none));
+            if let Some(free) = free.filter(|&free| free + len <= start) {
+                blocks[free..free + len].fill(Some(id));
+                blocks[start..start + len].fill(None);
+            }
+        }
+    }
+
+    /// Disk maps from a small linear congruential generator.
+    fn maps() -> impl Iterator<Item = Vec<u32>> {
+        let mut state = 12345u64;
+        (0..200).map(move |_| {
+            (0..1 + state % 40)
+                .map(|i| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    let digit = (state >> 33) as u32 % 10;
+                    if i % 2 == 0 {
+                        digit.max(1)
+                    } else {
+                        digit
+                    }
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_gaps() {
+        let disk = Disk::from_map(&[1, 2, 0, 3, 2, 1]);
+        assert_eq!(disk.gaps(), [Span::new(1, 5), Span::new(8, 1)]);
+        assert_eq!(disk.len(), 9);
+        assert_eq!(disk.extents()[1], Extent::new(2, 6, 2));
+    }
+
+    #[test]
+    fn test_matches_naive() {
+        for map in maps() {
+            let disk = Disk::from_map(&map);
+            let mut blocks = disk.blocks();
+            naive_compact_blocks(&mut blocks);
+            let mut compacted = disk.clone();
+            compacted.compact_blocks();
+            assert_eq!(compacted.blocks(), blocks, "{:?}", map);
+
+            let mut blocks = disk.blocks();
+            naive_compact_files(&mut blocks);
+            let mut compacted = disk.clone();
+            compacted.compact_files();
+            assert_eq!(compacted.blocks(), blocks, "{:?}", map);
+        }
+    }
+
+    #[test]
+    fn test_matches_naive_from_blocks() {
+        for map in maps() {
+            // Reversed, the files sit in the gaps' way, so moving one frees
+            // space that files tried later can use.
+            let mut blocks = Disk::from_map(&map).blocks();
+            blocks.reverse();
+            let mut disk = Disk::from_blocks(&blocks);
+            naive_compact_files(&mut blocks);
+            disk.compact_files();
+            assert_eq!(disk.blocks(), blocks, "{:?}", map);
+            // Compacting again starts from a disk that's already compacted.
+            naive_compact_files(&mut blocks);
+            disk.compact_files();
+            assert_eq!(disk.blocks(), blocks, "{:?}", map);
+        }
+        let mut disk = Disk::from_blocks(&[None, None, Some(1), Some(1), None, Some(0)]);
+        disk.compact_files();
+        assert_eq!(disk.to_string(), "110...");
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut disk = Disk::from_map(&[2, 3, 3, 3, 1, 0, 2]);
+        let mut moves = Vec::new();
+        disk.compact_files_with(|step| moves.push(step.to_string()));
+        assert_eq!(moves, ["file 3: 12..14 -> 2..4", "file 2: 11..12 -> 4..5"]);
+
+        let mut disk = Disk::from_map(&[1, 1, 1, 1, 3]);
+        let mut moves = Vec::new();
+        disk.compact_blocks_with(|step| moves.push(step.to_string()));
+        assert_eq!(moves, ["file 2: 6..7 -> 1..2", "file 2: 5..6 -> 3..4"]);
+        assert_eq!(disk.to_string(), "02122..");
+
+        // Replaying the moves on the blocks animates the compaction.
+        for map in maps() {
+            let mut disk = Disk::from_map(&map);
+            let mut blocks = disk.blocks();
+            disk.compact_blocks_with(|step| step.apply(&mut blocks));
+            assert_eq!(blocks, disk.blocks());
+            let mut disk = Disk::from_map(&map);
+            let mut blocks = disk.blocks();
+            disk.compact_files_with(|step| step.apply(&mut blocks));
+            assert_eq!(blocks, disk.blocks());
+        }
+    }
+
+    #[test]
+    fn test_fragmentation() {
+        let mut disk = Disk::from_map(&[1, 3, 1, 1, 1]);
+        assert_eq!(disk.fragmentation(), 0.25);
+        disk.compact_blocks();
+        assert_eq!(disk.fragmentation(), 0.0);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let disk = Disk::from_map(&[2, 3, 3, 3, 1]);
+        let blocks = disk.blocks();
+        let expected = blocks
+            .iter()
+            .enumerate()
+            .map(|(i, b)| b.map_or(0, |id| i as u64 * id as u64))
+            .sum();
+        assert_eq!(disk.checksum(), expected);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{Disk, Extent, Span};
+use solution::ParseError;
+use std::fmt;
+
+/// How [`Disk::render`] draws blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Render {
+    /// One character per block, as in the puzzle. Ids past 9 show as `#`.
+    #[default]
+    Digits,
+    /// Blocks separated by spaces and padded to the widest id, so any id
+    /// fits.
+    Wide,
+}
+
+/// Why a disk can't be written as a dense disk map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    /// The map numbers files by position, so they have to appear in id
+    /// order, each in one piece.
+    OutOfOrder { id: u32, offset: usize },
+    /// Each length has to fit in one digit.
+    TooLong(Span),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::OutOfOrder { id, offset } => {
+                write!(f, "file {} at block {} is out of order", id, offset)
+            }
+            MapError::TooLong(span) => write!(
+                f,
+                "{} blocks at block {} don't fit in one digit",
+                span.len, span.offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+/// Reads a dense disk map: digits only, alternating file and free lengths
+/// and starting with a file. An odd-length map ends with a file and an even
+/// one with free space.
+pub fn parse_map(input: &str) -> Result<Vec<u32>, ParseError> {
+    let input = input.trim_end();
+    if input.is_empty() {
+        return Err(ParseError::new("expected a disk map, found nothing").on_line(1));
+    }
+    input
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10).ok_or_else(|| {
+                ParseError::at_column(i + 1, format!("expected a digit, found `{}`", c)).on_line(1)
+            })
+        })
+        .collect()
+}
+
+impl Disk {
+    /// Builds a disk from the file id in each block, or `None` where it's
+    /// free.
+    pub fn from_blocks(blocks: &[Option<u32>]) -> Self {
+        let mut extents = Vec::<Extent>::new();
+        for (offset, &block) in blocks.iter().enumerate() {
+            let Some(id) = block else { continue };
+            match extents.last_mut() {
+                Some(last) if last.id == id && last.span.end() == offset => last.span.len += 1,
+                _ => extents.push(Extent::new(id, offset, 1)),
+            }
+        }
+        Self::from_extents(extents, blocks.len())
+    }
+
+    /// Reads a layout drawn by [`Disk::render`] in `style`. The style has to
+    /// be given: a wide layout of a single block, like `10`, would read as
+    /// two blocks of digits.
+    pub fn from_layout(layout: &str, style: Render) -> Result<Self, ParseError> {
+        let layout = layout.trim_end();
+        let tokens: Vec<(usize, &str)> = match style {
+            Render::Digits => layout
+                .char_indices()
+                .map(|(i, c)| (i, &layout[i..i + c.len_utf8()]))
+                .collect(),
+            Render::Wide => layout
+                .split_whitespace()
+                .map(|token| (token.as_ptr() as usize - layout.as_ptr() as usize, token))
+                .collect(),
+        };
+        let blocks = tokens
+            .into_iter()
+            .map(|(i, token)| {
+                if token.chars().all(|c| c == '.') {
+                    return Ok(None);
+                }
+                token.parse().map(Some).map_err(|_| {
+                    let message = format!("expected a file id or `.`, found `{}`", token);
+                    ParseError::at_column(i + 1, message).on_line(1)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_blocks(&blocks))
+    }
+
+    /// Draws the disk one block at a time, with `.` for free blocks.
+    pub fn render(&self, style: Render) -> String {
+        let blocks = self.blocks();
+        match style {
+            Render::Digits => blocks
+                .iter()
+                .map(|block| match block {
+                    Some(id) => char::from_digit(*id, 10).unwrap_or('#'),
+                    None => '.',
+                })
+                .collect(),
+            Render::Wide => {
+                let width = self
+                    .extents()
+                    .iter()
+                    .map(|extent| extent.id.to_string().len())
+                    .max()
+                    .unwrap_or(1);
+                let free = ".".repeat(width);
+                blocks
+                    .iter()
+                    .map(|block| match block {
+                        Some(id) => format!("{:>width$}", id),
+                        None => free.clone(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+        }
+    }
+
+    /// Writes the disk back as a dense disk map. Files missing from the disk
+    /// are written with length 0, and free space at the end as a final free
+    /// length.
+    pub fn to_map(&self) -> Result<String, MapError> {
+        let mut lengths = Vec::new();
+        let mut end = 0;
+        for run in self.runs() {
+            let gap = Span::new(end, run.span.offset - end);
+            if lengths.is_empty() && gap.len > 0 {
+                // The map can only start with free space after an empty
+                // file 0.
+                if run.id == 0 {
+                    return Err(MapError::OutOfOrder {
+                        id: 0,
+                        offset: run.span.offset,
+                    });
+                }
+                lengths.push(Span::new(0, 0));
+            }
+            if !lengths.is_empty() {
+                lengths.push(gap);
+            }
+            if (run.id as usize) < lengths.len() / 2 {
+                return Err(MapError::OutOfOrder {
+                    id: run.id,
+                    offset: run.span.offset,
+                });
+            }
+            while lengths.len() / 2 < run.id as usize {
+                lengths.extend([Span::new(run.span.offset, 0); 2]);
+            }
+            lengths.push(run.span);
+            end = run.span.end();
+        }
+        if end < self.len() {
+            if lengths.is_empty() {
+                lengths.push(Span::new(0, 0));
+            }
+            lengths.push(Span::new(end, self.len() - end));
+        }
+        lengths
+            .into_iter()
+            .map(|span| {
+                u32::try_from(span.len)
+                    .ok()
+                    .and_then(|len| char::from_digit(len, 10))
+                    .ok_or(MapError::TooLong(span))
+            })
+            .collect()
+    }
+
+    /// The extents with neighbouring pieces of the same file joined up.
+    fn runs(&self) -> Vec<Extent> {
+        let mut runs = Vec::<Extent>::new();
+        for &extent in self.extents() {
+            match runs.last_mut() {
+                Some(last) if last.id == extent.id && last.span.end() == extent.span.offset => {
+                    last.span.len += extent.span.len
+                }
+                _ => runs.push(extent),
+            }
+        }
+        runs
+    }
+}
+
+/// Draws the disk as in the puzzle; see [`Render::Digits`].
+impl fmt::Display for Disk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(Render::Digits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_map() {
+        assert_eq!(parse_map("12345\n").unwrap(), [1, 2, 3, 4, 5]);
+        assert_eq!(
+            parse_map("12a45").unwrap_err().to_string(),
+            "line 1, column 3: expected a digit, found `a`"
+        );
+        assert_eq!(
+            parse_map("\n").unwrap_err().to_string(),
+            "line 1: expected a disk map, found nothing"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let disk = Disk::from_map(&[1, 2, 3, 0, 1]);
+        assert_eq!(disk.to_string(), "0..1112");
+        assert_eq!(disk.render(Render::Wide), "0 . . 1 1 1 2");
+        let disk = Disk::from_map(&[1; 23]);
+        assert_eq!(disk.to_string(), "0.1.2.3.4.5.6.7.8.9.#.#");
+        assert!(disk.render(Render::Wide).starts_with(" 0 ..  1 .."));
+        assert!(disk.render(Render::Wide).ends_with(" 9 .. 10 .. 11"));
+        assert_eq!(
+            Disk::from_layout(&disk.render(Render::Wide), Render::Wide).unwrap(),
+            disk
+        );
+        // A disk holding only file 10 is one block wide.
+        let disk = Disk::from_blocks(&[Some(10)]);
+        assert_eq!(disk.render(Render::Wide), "10");
+        assert_eq!(Disk::from_layout("10", Render::Wide).unwrap(), disk);
+        assert_eq!(Disk::from_layout("10", Render::Digits).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for map in ["2333133121414131402", "1234", "0312", "10203"] {
+            let disk = Disk::from_map(&parse_map(map).unwrap());
+            let encoded = disk.to_map().unwrap();
+            assert_eq!(Disk::from_map(&parse_map(&encoded).unwrap()), disk);
+            assert_eq!(
+                Disk::from_layout(&disk.to_string(), Render::Digits).unwrap(),
+                disk
+            );
+        }
+        assert_eq!(Disk::from_map(&[2, 3, 3]).to_map().unwrap(), "233");
+        assert_eq!(Disk::from_map(&[0, 3, 3, 1]).to_map().unwrap(), "0331");
+    }
+
+    #[test]
+    fn test_unencodable() {
+        let mut disk = Disk::from_map(&[2, 3, 3, 3, 1]);
+        disk.compact_files();
+        assert_eq!(disk.to_string(), "002..111....");
+        assert_eq!(
+            disk.to_map().unwrap_err().to_string(),
+            "file 1 at block 5 is out of order"
+        );
+        let disk = Disk::from_blocks(&[Some(0); 10]);
+        assert_eq!(disk.to_map(), Err(MapError::TooLong(Span::new(0, 10))));
+        assert_eq!(
+            Disk::from_layout("0.x", Render::Digits)
+                .unwrap_err()
+                .to_string(),
+            "line 1, column 3: expected a file id or `.`, found `x`"
+        );
+    }
+}
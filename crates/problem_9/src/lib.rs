@@ -1,125 +1,70 @@
-pub fn part_1(input: &str) -> Option<u64> {
-    let nums = parse_digits(input);
-    let mut blocks = construct_blocks(&nums);
-    defrag_string(&mut blocks);
-    let result = checksum(&blocks);
-    Some(result as u64)
-}
+use solution::{Answer, ParseError, Solution};
 
-fn parse_digits(input: &str) -> Vec<u32> {
-    input
-        .chars()
-        .take_while(|c| c.is_ascii_digit())
-        .filter_map(|c| c.to_digit(10))
-        .collect()
-}
+pub use disk::{Disk, Extent, Move, Span};
+pub use layout::{parse_map, MapError, Render};
+pub use strategy::{
+    compare, BestFit, Blocks, FirstFit, Report, SplitAtGaps, Strategy, TowardEnd, WorstFit,
+    STRATEGIES,
+};
+
+mod disk;
+mod layout;
+mod strategy;
+
+pub struct Day9;
 
-fn defrag_string(blocks: &mut [Option<u32>]) {
-    let mut left = 0;
-    let mut right = blocks.len() - 1;
-    while left < right {
-        if blocks[left].is_none() && blocks[right].is_some() {
-            blocks.swap(left, right);
-        } else if blocks[left].is_none() {
-            right -= 1;
-        } else {
-            left += 1;
-        }
+impl Solution for Day9 {
+    type Input = Vec<u32>;
+
+    const DAY: u32 = 9;
+    const TITLE: &'static str = "Disk Fragmenter";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_map(input)
     }
-}
 
-fn defrag_files(blocks: &mut [Option<u32>]) {
-    let mut right = blocks.len() - 1;
-    while right > 0 {
-        if let Some(file_id) = blocks[right] {
-            let mut left = right;
-            while left > 0 && blocks[left] == blocks[right] {
-                left -= 1;
-            }
-            left += 1;
-            let file_size = right - left + 1;
-            println!(
-                "file_id: {}, left: {}, right: {}, size: {}",
-                file_id, left, right, file_size
-            );
-            // find first empty block of the same size
-            for i in 0..left {
-                if blocks[i..i + file_size].iter().all(|&b| b.is_none()) {
-                    blocks[i..i + file_size]
-                        .iter_mut()
-                        .for_each(|b| *b = Some(file_id));
-                    blocks[left..=right].iter_mut().for_each(|b| *b = None);
-                    break;
-                }
-            }
-            right = left - 1;
-        } else {
-            right -= 1;
-        }
+    fn part_1(nums: &Self::Input) -> Answer {
+        compact_blocks(nums).into()
     }
-}
 
-fn construct_blocks(map: &[u32]) -> Vec<Option<u32>> {
-    let mut blocks = Vec::new();
-    let mut file_id = 0;
-    let mut is_space = false;
-    for &num in map {
-        for _ in 0..num {
-            if is_space {
-                blocks.push(None);
-            } else {
-                blocks.push(Some(file_id));
-            }
-        }
-        if !is_space {
-            file_id += 1;
-        }
-        is_space = !is_space;
+    fn part_2(nums: &Self::Input) -> Answer {
+        compact_files(nums).into()
     }
-    blocks
 }
 
-fn checksum(blocks: &[Option<u32>]) -> u64 {
-    let mut sum = 0;
-    for (i, &block) in blocks.iter().enumerate() {
-        if let Some(block) = block {
-            sum += i as u64 * block as u64;
-        }
-    }
-    sum
+pub fn part_1(input: &str) -> Option<u64> {
+    Some(compact_blocks(&parse_map(input).ok()?))
+}
+
+fn compact_blocks(nums: &[u32]) -> u64 {
+    let mut disk = Disk::from_map(nums);
+    disk.compact_blocks();
+    disk.checksum()
 }
 
 pub fn part_2(input: &str) -> Option<u64> {
-    let nums = parse_digits(input);
-    let mut blocks = construct_blocks(&nums);
-    defrag_files(&mut blocks);
-    let result = checksum(&blocks);
-    Some(result as u64)
+    Some(compact_files(&parse_map(input).ok()?))
+}
+
+fn compact_files(nums: &[u32]) -> u64 {
+    let mut disk = Disk::from_map(nums);
+    disk.compact_files();
+    disk.checksum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn block_as_string(block: Option<u32>) -> String {
-        match block {
-            Some(id) => id.to_string(),
-            None => ".".to_string(),
-        }
-    }
-
-    fn blocks_as_string(blocks: &[Option<u32>]) -> String {
-        blocks.iter().map(|b| block_as_string(*b)).collect()
-    }
-
     const INPUT: &str = "2333133121414131402";
 
     #[test]
     fn test_construct() {
-        let nums = parse_digits(INPUT);
-        let result = construct_blocks(&nums);
+        let nums = parse_map(INPUT).unwrap();
+        let result = Disk::from_map(&nums);
         let expected = "00...111...2...333.44.5555.6666.777.888899";
-        assert_eq!(blocks_as_string(&result), expected.to_string());
+        assert_eq!(result.to_string(), expected);
+        assert_eq!(result.to_map().unwrap(), INPUT);
     }
 
     #[test]
@@ -130,13 +75,11 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        // let result = part_2(INPUT);
-        let nums = parse_digits(INPUT);
-        let mut blocks = construct_blocks(&nums);
-        println!("{:?}", blocks_as_string(&blocks));
-        defrag_files(&mut blocks);
-        println!("{:?}", blocks_as_string(&blocks));
-        let result = checksum(&blocks);
-        assert_eq!(Some(result), Some(2858));
+        let nums = parse_map(INPUT).unwrap();
+        let mut disk = Disk::from_map(&nums);
+        disk.compact_files();
+        let expected = "00992111777.44.333....5555.6666.....8888..";
+        assert_eq!(disk.to_string(), expected);
+        assert_eq!(part_2(INPUT), Some(2858));
     }
 }
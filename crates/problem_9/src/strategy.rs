@@ -0,0 +1,416 @@
+use crate::{Disk, Extent, Move, Span};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// A policy for compacting a disk.
+pub trait Strategy {
+    /// A short name for reports.
+    fn name(&self) -> &'static str;
+
+    /// Compacts `disk`, calling `observe` with each move as it's made.
+    fn compact(&self, disk: &mut Disk, observe: &mut dyn FnMut(Move));
+}
+
+/// Every strategy, in the order [`compare`] reports them.
+pub const STRATEGIES: [&dyn Strategy; 6] = [
+    &Blocks,
+    &FirstFit,
+    &BestFit,
+    &WorstFit,
+    &SplitAtGaps,
+    &TowardEnd,
+];
+
+/// Part 1: moves blocks one at a time from the end of the disk into the
+/// leftmost free block, until there are no gaps between files.
+#[derive(Debug, Clone, Copy)]
+pub struct Blocks;
+
+/// Part 2: moves each file whole, highest id first, into the leftmost gap
+/// before it that fits.
+#[derive(Debug, Clone, Copy)]
+pub struct FirstFit;
+
+/// Like [`FirstFit`], but into the smallest gap that fits, leftmost on ties.
+#[derive(Debug, Clone, Copy)]
+pub struct BestFit;
+
+/// Like [`FirstFit`], but into the largest gap, leftmost on ties.
+#[derive(Debug, Clone, Copy)]
+pub struct WorstFit;
+
+/// Moves each file, highest id first, into the leftmost gaps before it,
+/// splitting it where a gap ends. A file only moves if all of it fits.
+#[derive(Debug, Clone, Copy)]
+pub struct SplitAtGaps;
+
+/// [`FirstFit`] mirrored: moves each file whole, lowest id first, to the end
+/// of the rightmost gap after it that fits.
+#[derive(Debug, Clone, Copy)]
+pub struct TowardEnd;
+
+impl Strategy for Blocks {
+    fn name(&self) -> &'static str {
+        "blocks"
+    }
+
+    fn compact(&self, disk: &mut Disk, observe: &mut dyn FnMut(Move)) {
+        let mut gaps = disk.gaps().into_iter();
+        let mut gap = gaps.next();
+        let mut extents = disk.extents().to_vec();
+        let mut moved = Vec::new();
+        while let Some(mut extent) = extents.pop() {
+            while let Some(free) = gap.as_mut().filter(|free| free.offset < extent.span.offset) {
+                let len = free.len.min(extent.span.len);
+                observe(Move {
+                    id: extent.id,
+                    from: Span::new(extent.span.end() - len, len),
+                    to: Span::new(free.offset, len),
+                });
+                moved.push(Extent::new(extent.id, free.offset, len));
+                free.offset += len;
+                free.len -= len;
+                extent.span.len -= len;
+                if free.len == 0 {
+                    gap = gaps.next();
+                }
+                if extent.span.len == 0 {
+                    break;
+                }
+            }
+            if extent.span.len > 0 {
+                extents.push(extent);
+                break;
+            }
+        }
+        extents.extend(moved);
+        disk.set_extents(extents);
+    }
+}
+
+impl Strategy for FirstFit {
+    fn name(&self) -> &'static str {
+        "first fit"
+    }
+
+    fn compact(&self, disk: &mut Disk, observe: &mut dyn FnMut(Move)) {
+        move_whole_files(disk, Direction::Start, observe, |free, span| {
+            free.fitting(span.len)
+                .filter_map(|(_, offsets)| offsets.range(..span.offset).next())
+                .min()
+                .copied()
+        });
+    }
+}
+
+impl Strategy for BestFit {
+    fn name(&self) -> &'static str {
+        "best fit"
+    }
+
+    fn compact(&self, disk: &mut Disk, observe: &mut dyn FnMut(Move)) {
+        move_whole_files(disk, Direction::Start, observe, |free, span| {
+            free.fitting(span.len)
+                .find_map(|(_, offsets)| offsets.range(..span.offset).next())
+                .copied()
+        });
+    }
+}
+
+impl Strategy for WorstFit {
+    fn name(&self) -> &'static str {
+        "worst fit"
+    }
+
+    fn compact(&self, disk: &mut Disk, observe: &mut dyn FnMut(Move)) {
+        move_whole_files(disk, Direction::Start, observe, |free, span| {
+            free.fitting(span.len)
+                .rev()
+                .find_map(|(_, offsets)| offsets.range(..span.offset).next())
+                .copied()
+        });
+    }
+}
+
+impl Strategy for TowardEnd {
+    fn name(&self) -> &'static str {
+        "toward end"
+    }
+
+    fn compact(&self, disk: &mut Disk, observe: &mut dyn FnMut(Move)) {
+        move_whole_files(disk, Direction::End, observe, |free, span| {
+            free.fitting(span.len)
+                .filter_map(|(_, offsets)| offsets.range(span.end()..).next_back())
+                .max()
+                .copied()
+        });
+    }
+}
+
+impl Strategy for SplitAtGaps {
+    fn name(&self) -> &'static str {
+        "split at gaps"
+    }
+
+    fn compact(&self, disk: &mut Disk, observe: &mut dyn FnMut(Move)) {
+        let mut free = FreeSpans::new(disk.gaps());
+        let mut extents = disk.extents().to_vec();
+        extents.sort_by_key(|extent| Reverse((extent.id, extent.span.offset)));
+        let mut compacted = Vec::new();
+        for extent in extents {
+            let mut gaps = Vec::new();
+            let mut room = 0;
+            for gap in free.before(extent.span.offset) {
+                if room >= extent.span.len {
+                    break;
+                }
+                room += gap.len;
+                gaps.push(gap);
+            }
+            if room < extent.span.len {
+                compacted.push(extent);
+                continue;
+            }
+            // Fill the gaps with the file's blocks front to back, so the
+            // pieces stay in order.
+            let mut from = extent.span.offset;
+            let mut remaining = extent.span.len;
+            for gap in gaps {
+                let len = gap.len.min(remaining);
+                let to = free.take(gap, len, Direction::Start);
+                observe(Move {
+                    id: extent.id,
+                    from: Span::new(from, len),
+                    to,
+                });
+                compacted.push(Extent {
+                    id: extent.id,
+                    span: to,
+                });
+                from += len;
+                remaining -= len;
+            }
+            free.release(extent.span);
+        }
+        disk.set_extents(compacted);
+    }
+}
+
+/// Which end of the disk files are moved toward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Start,
+    End,
+}
+
+/// Tries each file once, farthest from the `direction` end first, and moves
+/// it whole into the gap `pick` returns the offset of, if any. The space a
+/// file leaves joins the gaps around it, so files tried later can move
+/// there.
+fn move_whole_files(
+    disk: &mut Disk,
+    direction: Direction,
+    observe: &mut dyn FnMut(Move),
+    pick: impl Fn(&FreeSpans, Span) -> Option<usize>,
+) {
+    let mut free = FreeSpans::new(disk.gaps());
+    let mut extents = disk.extents().to_vec();
+    match direction {
+        Direction::Start => extents.sort_by_key(|extent| Reverse((extent.id, extent.span.offset))),
+        Direction::End => extents.sort_by_key(|extent| (extent.id, extent.span.offset)),
+    }
+    for extent in &mut extents {
+        let Some(offset) = pick(&free, extent.span) else {
+            continue;
+        };
+        let gap = Span::new(offset, free.by_offset[&offset]);
+        let to = free.take(gap, extent.span.len, direction);
+        observe(Move {
+            id: extent.id,
+            from: extent.span,
+            to,
+        });
+        free.release(extent.span);
+        extent.span = to;
+    }
+    disk.set_extents(extents);
+}
+
+/// Free spans by offset, and bucketed by length with each bucket sorted by
+/// offset, so the leftmost or rightmost span with room for a file is one
+/// lookup per bucket away.
+struct FreeSpans {
+    by_offset: BTreeMap<usize, usize>,
+    by_len: Vec<BTreeSet<usize>>,
+}
+
+impl FreeSpans {
+    fn new(spans: impl IntoIterator<Item = Span>) -> Self {
+        let mut free = Self {
+            by_offset: BTreeMap::new(),
+            by_len: Vec::new(),
+        };
+        for span in spans {
+            free.insert(span);
+        }
+        free
+    }
+
+    fn insert(&mut self, span: Span) {
+        if span.len == 0 {
+            return;
+        }
+        if span.len >= self.by_len.len() {
+            self.by_len.resize_with(span.len + 1, BTreeSet::new);
+        }
+        self.by_len[span.len].insert(span.offset);
+        self.by_offset.insert(span.offset, span.len);
+    }
+
+    fn remove(&mut self, span: Span) {
+        self.by_len[span.len].remove(&span.offset);
+        self.by_offset.remove(&span.offset);
+    }
+
+    /// Frees `span`, joining it up with the free spans on either side.
+    fn release(&mut self, mut span: Span) {
+        let left = self.before(span.offset).last();
+        if let Some(left) = left.filter(|left| left.end() == span.offset) {
+            self.remove(left);
+            span = Span::new(left.offset, left.len + span.len);
+        }
+        if let Some(&len) = self.by_offset.get(&span.end()) {
+            self.remove(Span::new(span.end(), len));
+            span.len += len;
+        }
+        self.insert(span);
+    }
+
+    /// The buckets of spans with room for `len` blocks, shortest first.
+    fn fitting(&self, len: usize) -> impl DoubleEndedIterator<Item = (usize, &BTreeSet<usize>)> {
+        self.by_len.iter().enumerate().skip(len)
+    }
+
+    /// The spans that start before `offset`, leftmost first.
+    fn before(&self, offset: usize) -> impl Iterator<Item = Span> + '_ {
+        self.by_offset
+            .range(..offset)
+            .map(|(&offset, &len)| Span::new(offset, len))
+    }
+
+    /// Takes `len` blocks from one end of `gap`, putting back the rest.
+    fn take(&mut self, gap: Span, len: usize, direction: Direction) -> Span {
+        self.remove(gap);
+        let rest = gap.len - len;
+        match direction {
+            Direction::Start => {
+                self.insert(Span::new(gap.offset + len, rest));
+                Span::new(gap.offset, len)
+            }
+            Direction::End => {
+                self.insert(Span::new(gap.offset, rest));
+                Span::new(gap.offset + rest, len)
+            }
+        }
+    }
+}
+
+/// How a strategy left a disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub strategy: &'static str,
+    pub checksum: u64,
+    pub fragmentation: f64,
+    pub moves: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: checksum {}, fragmentation {:.3}, {} moves",
+            self.strategy, self.checksum, self.fragmentation, self.moves
+        )
+    }
+}
+
+/// Compacts a copy of `disk` with each of [`STRATEGIES`].
+pub fn compare(disk: &Disk) -> Vec<Report> {
+    STRATEGIES
+        .iter()
+        .map(|strategy| {
+            let mut disk = disk.clone();
+            let mut moves = 0;
+            strategy.compact(&mut disk, &mut |_| moves += 1);
+            Report {
+                strategy: strategy.name(),
+                checksum: disk.checksum(),
+                fragmentation: disk.fragmentation(),
+                moves,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Render;
+
+    fn compact(strategy: &dyn Strategy, map: &[u32]) -> String {
+        compact_disk(strategy, Disk::from_map(map))
+    }
+
+    fn compact_disk(strategy: &dyn Strategy, mut disk: Disk) -> String {
+        let mut blocks = disk.blocks();
+        strategy.compact(&mut disk, &mut |step| step.apply(&mut blocks));
+        assert_eq!(blocks, disk.blocks(), "{}", strategy.name());
+        disk.to_string()
+    }
+
+    #[test]
+    fn test_fits() {
+        let map = [2, 4, 1, 2, 2, 3, 1];
+        assert_eq!(Disk::from_map(&map).to_string(), "00....1..22...3");
+        assert_eq!(compact(&FirstFit, &map), "003221.........");
+        assert_eq!(compact(&BestFit, &map), "00221..3.......");
+        assert_eq!(compact(&WorstFit, &map), "003221.........");
+        assert_eq!(compact(&TowardEnd, &map), ".........221003");
+    }
+
+    #[test]
+    fn test_reuses_freed_space() {
+        let disk = Disk::from_layout("..11.0", Render::Digits).unwrap();
+        assert_eq!(compact_disk(&FirstFit, disk.clone()), "110...");
+        assert_eq!(compact_disk(&BestFit, disk.clone()), "110...");
+        assert_eq!(compact_disk(&WorstFit, disk.clone()), "110...");
+        assert_eq!(compact_disk(&SplitAtGaps, disk), "110...");
+        let disk = Disk::from_layout("1.00..", Render::Digits).unwrap();
+        assert_eq!(compact_disk(&TowardEnd, disk), "...100");
+    }
+
+    #[test]
+    fn test_split_at_gaps() {
+        let map = [1, 1, 1, 2, 3];
+        assert_eq!(Disk::from_map(&map).to_string(), "0.1..222");
+        assert_eq!(compact(&SplitAtGaps, &map), "02122...");
+        // A file that doesn't fit in the gaps before it stays put.
+        assert_eq!(compact(&SplitAtGaps, &[1, 1, 1, 1, 3]), "01..222");
+        assert_eq!(compact(&Blocks, &[1, 1, 1, 1, 3]), "02122..");
+    }
+
+    #[test]
+    fn test_compare() {
+        let disk = Disk::from_map(&crate::parse_map("2333133121414131402").unwrap());
+        let reports = compare(&disk);
+        assert_eq!(reports.len(), STRATEGIES.len());
+        assert_eq!(reports[0].checksum, 1928);
+        assert_eq!(reports[0].fragmentation, 0.0);
+        assert_eq!(reports[1].checksum, 2858);
+        assert_eq!(
+            reports[1].to_string(),
+            "first fit: checksum 2858, fragmentation 0.643, 4 moves"
+        );
+    }
+}
@@ -1,3 +1,26 @@
+use solution::{Answer, ParseError, Solution};
+
+pub struct Day0;
+
+impl Solution for Day0 {
+    type Input = String;
+
+    const DAY: u32 = 0;
+    const TITLE: &'static str = "";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
+}
+
 pub fn part_1(_input: &str) -> Option<i32> {
     None
 }
@@ -0,0 +1,75 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The inputs directory checked into the repo, used when `--inputs` isn't given.
+pub const DEFAULT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/inputs");
+
+/// Where to read puzzle inputs from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A directory holding one `<day>.txt` per day.
+    Dir(PathBuf),
+    /// A single file, used regardless of the day.
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Dir(PathBuf::from(DEFAULT_DIR))
+    }
+}
+
+impl Source {
+    /// `-` means stdin, anything else is a file path.
+    pub fn file(path: &str) -> Self {
+        if path == "-" {
+            Source::Stdin
+        } else {
+            Source::File(PathBuf::from(path))
+        }
+    }
+
+    pub fn load(&self, day: u32) -> Result<String, String> {
+        match self {
+            Source::Dir(dir) => read(&dir.join(format!("{}.txt", day)))
+                .map_err(|e| format!("no input for day {}: {}", day, e)),
+            Source::File(path) => read(path),
+            Source::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("failed to read stdin: {}", e))?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_from_dir() {
+        let input = Source::default().load(11).unwrap();
+        assert!(!input.trim().is_empty());
+    }
+
+    #[test]
+    fn test_missing_day() {
+        let err = Source::default().load(99).unwrap_err();
+        assert!(err.starts_with("no input for day 99: "), "{}", err);
+        assert!(err.contains("99.txt"), "{}", err);
+    }
+
+    #[test]
+    fn test_file_or_stdin() {
+        assert_eq!(Source::file("-"), Source::Stdin);
+        assert_eq!(Source::file("a.txt"), Source::File(PathBuf::from("a.txt")));
+    }
+}
@@ -1,7 +1,349 @@
-use problem_11::{part_1, part_2};
+use input::Source;
+use output::{Format, Record, Status};
+use solution::Puzzle;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../inputs/11.txt");
-    println!("part1: {:?}", part_1(input));
-    println!("part2: {:?}", part_2(input));
+mod input;
+mod output;
+mod registry;
+mod scaffold;
+mod timing;
+mod verify;
+
+const USAGE: &str = "usage: runner [verify|bench|new] <day|all> [--part <1|2>] [--iterations <n>] [--format <text|json>] [--inputs <dir>] [--input <file|->]";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    Run,
+    Verify,
+    Bench { iterations: usize },
+    New,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Selection {
+    All,
+    Day(u32),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    mode: Mode,
+    selection: Selection,
+    part: Option<usize>,
+    source: Source,
+    format: Format,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut mode = None;
+    let mut selection = None;
+    let mut part = None;
+    let mut dir = None;
+    let mut file = None;
+    let mut iterations = None;
+    let mut format = Format::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                let value = args.next().ok_or("--part needs a value")?;
+                part = match value.as_str() {
+                    "1" => Some(1),
+                    "2" => Some(2),
+                    _ => return Err(format!("invalid part `{}`", value)),
+                };
+            }
+            "--iterations" | "-n" => {
+                let value = args.next().ok_or("--iterations needs a value")?;
+                let n = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or(format!("invalid iteration count `{}`", value))?;
+                iterations = Some(n);
+            }
+            "--format" | "-f" => {
+                format = args.next().ok_or("--format needs a value")?.parse()?;
+            }
+            "--inputs" => dir = Some(args.next().ok_or("--inputs needs a value")?),
+            "--input" | "-i" => file = Some(args.next().ok_or("--input needs a value")?),
+            "verify" if mode.is_none() && selection.is_none() => mode = Some(Mode::Verify),
+            "bench" if mode.is_none() && selection.is_none() => {
+                mode = Some(Mode::Bench { iterations: 0 })
+            }
+            "new" if mode.is_none() && selection.is_none() => mode = Some(Mode::New),
+            "all" if selection.is_none() => selection = Some(Selection::All),
+            _ if selection.is_none() => {
+                let day = arg
+                    .parse::<u32>()
+                    .map_err(|_| format!("invalid day `{}`", arg))?;
+                selection = Some(Selection::Day(day));
+            }
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+    let mode = match (mode.unwrap_or(Mode::Run), iterations) {
+        (Mode::Bench { .. }, n) => Mode::Bench {
+            iterations: n.unwrap_or(timing::DEFAULT_ITERATIONS),
+        },
+        (_, Some(_)) => return Err("--iterations only applies to bench".to_string()),
+        (mode, None) => mode,
+    };
+    if format == Format::Json && mode != Mode::Run {
+        return Err("--format json only applies to running days".to_string());
+    }
+    let selection = match mode {
+        Mode::Run => selection.ok_or("missing day")?,
+        Mode::New => match selection {
+            Some(Selection::Day(day)) => Selection::Day(day),
+            _ => return Err("new needs a day number".to_string()),
+        },
+        Mode::Verify | Mode::Bench { .. } => selection.unwrap_or(Selection::All),
+    };
+    let source = match (dir, file) {
+        (Some(_), Some(_)) => return Err("--inputs and --input are exclusive".to_string()),
+        (_, Some(_)) if mode == Mode::Verify => {
+            return Err("verify reads answers from an inputs directory".to_string())
+        }
+        (_, Some(_)) if selection == Selection::All => {
+            return Err("--input needs a single day".to_string())
+        }
+        (_, Some(file)) => Source::file(&file),
+        (Some(dir), None) => Source::Dir(PathBuf::from(dir)),
+        (None, None) => Source::default(),
+    };
+    Ok(Args {
+        mode,
+        selection,
+        part,
+        source,
+        format,
+    })
+}
+
+fn run_day(
+    puzzle: &dyn Puzzle,
+    part: Option<usize>,
+    source: &Source,
+    format: Format,
+) -> Result<(), String> {
+    let day = puzzle.day();
+    let parts = (1..=2)
+        .filter(|&p| part.is_none_or(|part| part == p))
+        .collect::<Vec<_>>();
+    let fail = |e: String| {
+        if format == Format::Json {
+            for &part in &parts {
+                let status = Status::Error(e.clone());
+                let record = Record {
+                    day,
+                    part,
+                    status,
+                    elapsed: None,
+                };
+                println!("{}", record.to_json());
+            }
+        }
+        Err(e)
+    };
+    let input = match source.load(day) {
+        Ok(input) => input,
+        Err(e) => return fail(e),
+    };
+    let (parsed, elapsed) = timing::time(|| puzzle.parse(&input));
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => return fail(format!("day {}: {}", day, e)),
+    };
+    if format == Format::Text {
+        println!("parse: ({:?})", elapsed);
+    }
+    for &part in &parts {
+        let (answer, elapsed) = timing::time(|| parsed.part(part));
+        match format {
+            Format::Text => println!("part{}: {} ({:?})", part, answer, elapsed),
+            Format::Json => {
+                let record = Record {
+                    day,
+                    part,
+                    status: answer.into(),
+                    elapsed: Some(elapsed),
+                };
+                println!("{}", record.to_json());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let (Mode::New, Selection::Day(day)) = (&args.mode, &args.selection) {
+        return match scaffold::scaffold(&scaffold::crates_dir(), *day) {
+            Ok(changed) => {
+                for path in changed {
+                    println!("wrote {}", path.display());
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let puzzles = match args.selection {
+        Selection::Day(day) => registry::find(day)
+            .map(|puzzle| vec![puzzle])
+            .ok_or(format!("day {} is not solved yet", day)),
+        Selection::All => Ok(registry::PUZZLES.to_vec()),
+    };
+
+    let result = puzzles.and_then(|puzzles| match (&args.mode, &args.source) {
+        (Mode::Run, source) => {
+            let mut failed = 0;
+            for puzzle in &puzzles {
+                if args.selection == Selection::All && args.format == Format::Text {
+                    println!("day {}: {}", puzzle.day(), puzzle.title());
+                }
+                if let Err(e) = run_day(*puzzle, args.part, source, args.format) {
+                    eprintln!("error: {}", e);
+                    failed += 1;
+                }
+            }
+            match failed {
+                0 => Ok(()),
+                n => Err(format!("{} of {} days failed", n, puzzles.len())),
+            }
+        }
+        (Mode::Bench { iterations }, source) => puzzles.iter().try_for_each(|puzzle| {
+            println!("day {}: {}", puzzle.day(), puzzle.title());
+            timing::bench(*puzzle, args.part, source, *iterations)
+        }),
+        (Mode::Verify, Source::Dir(dir)) => match verify::verify(&puzzles, dir) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("some answers did not match".to_string()),
+            Err(e) => Err(e),
+        },
+        (Mode::Verify, _) => unreachable!("verify always reads from a directory"),
+        (Mode::New, _) => unreachable!("new is handled before selecting puzzles"),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_day_and_part() {
+        let result = args(&["6", "--part", "2"]);
+        assert_eq!(
+            result,
+            Ok(Args {
+                mode: Mode::Run,
+                selection: Selection::Day(6),
+                part: Some(2),
+                source: Source::default(),
+                format: Format::Text,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let result = args(&["all"]);
+        assert_eq!(
+            result,
+            Ok(Args {
+                mode: Mode::Run,
+                selection: Selection::All,
+                part: None,
+                source: Source::default(),
+                format: Format::Text,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        let result = args(&["verify"]).map(|a| (a.mode, a.selection));
+        assert_eq!(result, Ok((Mode::Verify, Selection::All)));
+        let result = args(&["verify", "5"]).map(|a| (a.mode, a.selection));
+        assert_eq!(result, Ok((Mode::Verify, Selection::Day(5))));
+        assert!(args(&["verify", "--input", "a.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let result = args(&["bench", "9", "-n", "3"]).map(|a| (a.mode, a.selection));
+        assert_eq!(
+            result,
+            Ok((Mode::Bench { iterations: 3 }, Selection::Day(9)))
+        );
+        let result = args(&["bench"]).map(|a| a.mode);
+        assert_eq!(
+            result,
+            Ok(Mode::Bench {
+                iterations: timing::DEFAULT_ITERATIONS
+            })
+        );
+        assert!(args(&["bench", "-n", "0"]).is_err());
+        assert!(args(&["9", "-n", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let result = args(&["all", "--format", "json"]).map(|a| a.format);
+        assert_eq!(result, Ok(Format::Json));
+        assert!(args(&["all", "--format", "yaml"]).is_err());
+        assert!(args(&["verify", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_new() {
+        let result = args(&["new", "12"]).map(|a| (a.mode, a.selection));
+        assert_eq!(result, Ok((Mode::New, Selection::Day(12))));
+        assert!(args(&["new"]).is_err());
+        assert!(args(&["new", "all"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_input_sources() {
+        let result = args(&["3", "--input", "-"]).map(|a| a.source);
+        assert_eq!(result, Ok(Source::Stdin));
+        let result = args(&["all", "--inputs", "elsewhere"]).map(|a| a.source);
+        assert_eq!(result, Ok(Source::Dir(PathBuf::from("elsewhere"))));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(args(&[]).is_err());
+        assert!(args(&["x"]).is_err());
+        assert!(args(&["6", "--part", "3"]).is_err());
+        assert!(args(&["6", "7"]).is_err());
+        assert!(args(&["all", "--input", "a.txt"]).is_err());
+        assert!(args(&["6", "--inputs", "x", "--input", "a.txt"]).is_err());
+    }
 }
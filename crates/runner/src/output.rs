@@ -0,0 +1,141 @@
+use solution::Answer;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("invalid format `{}`", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Solved(i128),
+    Unsolved,
+    Error(String),
+}
+
+impl From<Answer> for Status {
+    fn from(answer: Answer) -> Self {
+        match answer {
+            Answer::Number(n) => Status::Solved(n),
+            Answer::Unsolved => Status::Unsolved,
+        }
+    }
+}
+
+/// The outcome of a single day/part, as emitted by `--format json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u32,
+    pub part: usize,
+    pub status: Status,
+    pub elapsed: Option<Duration>,
+}
+
+impl Record {
+    pub fn to_json(&self) -> String {
+        let (status, answer, error) = match &self.status {
+            Status::Solved(n) => ("solved", n.to_string(), None),
+            Status::Unsolved => ("unsolved", "null".to_string(), None),
+            Status::Error(e) => ("error", "null".to_string(), Some(e)),
+        };
+        let elapsed = self
+            .elapsed
+            .map_or("null".to_string(), |d| d.as_micros().to_string());
+        let mut json = format!(
+            "{{\"day\":{},\"part\":{},\"status\":\"{}\",\"answer\":{},\"elapsed_us\":{}",
+            self.day, self.part, status, answer, elapsed
+        );
+        if let Some(error) = error {
+            json.push_str(&format!(",\"error\":{}", json_string(error)));
+        }
+        json.push('}');
+        json
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solved_json() {
+        let record = Record {
+            day: 6,
+            part: 2,
+            status: Answer::Number(1575).into(),
+            elapsed: Some(Duration::from_micros(1234)),
+        };
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":6,"part":2,"status":"solved","answer":1575,"elapsed_us":1234}"#
+        );
+    }
+
+    #[test]
+    fn test_unsolved_json() {
+        let record = Record {
+            day: 12,
+            part: 1,
+            status: Answer::Unsolved.into(),
+            elapsed: Some(Duration::ZERO),
+        };
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":12,"part":1,"status":"unsolved","answer":null,"elapsed_us":0}"#
+        );
+    }
+
+    #[test]
+    fn test_error_json() {
+        let record = Record {
+            day: 3,
+            part: 1,
+            status: Status::Error("no \"input\"\n".to_string()),
+            elapsed: None,
+        };
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":3,"part":1,"status":"error","answer":null,"elapsed_us":null,"error":"no \"input\"\n"}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use solution::Puzzle;
+
+/// Every solved day, in order.
+pub static PUZZLES: &[&dyn Puzzle] = &[
+    &problem_1::Day1,
+    &problem_2::Day2,
+    &problem_3::Day3,
+    &problem_4::Day4,
+    &problem_5::Day5,
+    &problem_6::Day6,
+    &problem_7::Day7,
+    &problem_8::Day8,
+    &problem_9::Day9,
+    &problem_10::Day10,
+    &problem_11::Day11,
+];
+
+pub fn find(day: u32) -> Option<&'static dyn Puzzle> {
+    PUZZLES.iter().copied().find(|p| p.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_unique_and_ordered() {
+        let days = PUZZLES.iter().map(|p| p.day()).collect::<Vec<_>>();
+        assert!(days.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The `crates/` directory this runner was built from.
+pub fn crates_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("runner lives inside the crates directory")
+        .to_path_buf()
+}
+
+/// Creates `problem_<day>` from `problem_template`, adds an empty input file
+/// and wires the new crate into the runner's manifest and registry.
+/// Returns the paths that were created or changed.
+pub fn scaffold(crates: &Path, day: u32) -> Result<Vec<PathBuf>, String> {
+    let template = crates.join("problem_template");
+    let target = crates.join(format!("problem_{}", day));
+    let runner = crates.join("runner");
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    let manifest_path = runner.join("Cargo.toml");
+    let registry_path = runner.join("src").join("registry.rs");
+    let manifest = add_dependency(&read(&manifest_path)?, day)?;
+    let registry = add_to_registry(&read(&registry_path)?, day)?;
+    let template_manifest = read(&template.join("Cargo.toml"))?;
+    let template_lib = read(&template.join("src").join("lib.rs"))?;
+
+    let mut changed = Vec::new();
+    let new_manifest = target.join("Cargo.toml");
+    let new_lib = target.join("src").join("lib.rs");
+    fs::create_dir_all(target.join("src")).map_err(|e| format!("{}: {}", target.display(), e))?;
+    write(&new_manifest, &crate_manifest(&template_manifest, day))?;
+    changed.push(new_manifest);
+    write(&new_lib, &crate_lib(&template_lib, day))?;
+    changed.push(new_lib);
+
+    let input = runner.join("inputs").join(format!("{}.txt", day));
+    if !input.exists() {
+        write(&input, "")?;
+        changed.push(input);
+    }
+
+    write(&manifest_path, &manifest)?;
+    changed.push(manifest_path);
+    write(&registry_path, &registry)?;
+    changed.push(registry_path);
+    Ok(changed)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn crate_manifest(template: &str, day: u32) -> String {
+    template.replace("problem_template", &format!("problem_{}", day))
+}
+
+fn crate_lib(template: &str, day: u32) -> String {
+    template
+        .replace("Day0", &format!("Day{}", day))
+        .replace("const DAY: u32 = 0;", &format!("const DAY: u32 = {};", day))
+}
+
+/// Number of the `problem_<n>` crate a line refers to, if any.
+fn problem_number(line: &str, prefix: &str) -> Option<u32> {
+    let rest = line.trim_start().strip_prefix(prefix)?;
+    let digits = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    digits.parse().ok()
+}
+
+/// Inserts `line` among the lines matching `prefix`, keeping them ordered by
+/// day. Falls back to inserting after the last matching line.
+fn insert_ordered(text: &str, prefix: &str, day: u32, line: String) -> Result<String, String> {
+    let mut lines = text.lines().map(str::to_string).collect::<Vec<_>>();
+    let numbered = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| problem_number(l, prefix).map(|n| (i, n)))
+        .collect::<Vec<_>>();
+    if numbered.iter().any(|&(_, n)| n == day) {
+        return Err(format!("problem_{} is already registered", day));
+    }
+    let index = match numbered.iter().find(|&&(_, n)| n > day) {
+        Some(&(i, _)) => i,
+        None => match numbered.last() {
+            Some(&(i, _)) => i + 1,
+            None => return Err(format!("no `{}` entries to extend", prefix)),
+        },
+    };
+    lines.insert(index, line);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn add_dependency(manifest: &str, day: u32) -> Result<String, String> {
+    let line = format!("problem_{0} = {{ path = \"../problem_{0}\" }}", day);
+    insert_ordered(manifest, "problem_", day, line)
+}
+
+fn add_to_registry(registry: &str, day: u32) -> Result<String, String> {
+    let line = format!("    &problem_{0}::Day{0},", day);
+    insert_ordered(registry, "&problem_", day, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "[dependencies]
+problem_1 = { path = \"../problem_1\" }
+problem_3 = { path = \"../problem_3\" }
+solution = { path = \"../solution\" }
+";
+
+    const REGISTRY: &str = "pub static PUZZLES: &[&dyn Puzzle] = &[
+    &problem_1::Day1,
+    &problem_3::Day3,
+];
+";
+
+    #[test]
+    fn test_add_dependency() {
+        let result = add_dependency(MANIFEST, 2).unwrap();
+        assert_eq!(
+            result,
+            "[dependencies]
+problem_1 = { path = \"../problem_1\" }
+problem_2 = { path = \"../problem_2\" }
+problem_3 = { path = \"../problem_3\" }
+solution = { path = \"../solution\" }
+"
+        );
+    }
+
+    #[test]
+    fn test_add_to_registry() {
+        let result = add_to_registry(REGISTRY, 12).unwrap();
+        assert_eq!(
+            result,
+            "pub static PUZZLES: &[&dyn Puzzle] = &[
+    &problem_1::Day1,
+    &problem_3::Day3,
+    &problem_12::Day12,
+];
+"
+        );
+        assert!(add_to_registry(REGISTRY, 3).is_err());
+    }
+
+    #[test]
+    fn test_crate_lib() {
+        let template = read(&crates_dir().join("problem_template/src/lib.rs")).unwrap();
+        let lib = crate_lib(&template, 12);
+        assert!(lib.contains("pub struct Day12;"));
+        assert!(lib.contains("impl Solution for Day12"));
+        assert!(lib.contains("const DAY: u32 = 12;"));
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = std::env::temp_dir().join(format!("runner-scaffold-{}", std::process::id()));
+        let crates = root.join("crates");
+        for dir in ["problem_template/src", "runner/src", "runner/inputs"] {
+            fs::create_dir_all(crates.join(dir)).unwrap();
+        }
+        let template = crates_dir().join("problem_template");
+        for file in ["Cargo.toml", "src/lib.rs"] {
+            fs::copy(
+                template.join(file),
+                crates.join("problem_template").join(file),
+            )
+            .unwrap();
+        }
+        fs::write(crates.join("runner/Cargo.toml"), MANIFEST).unwrap();
+        fs::write(crates.join("runner/src/registry.rs"), REGISTRY).unwrap();
+
+        let changed = scaffold(&crates, 4).unwrap();
+        assert_eq!(changed.len(), 5);
+        let manifest = read(&crates.join("problem_4/Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"problem_4\""));
+        assert!(crates.join("runner/inputs/4.txt").exists());
+        let registry = read(&crates.join("runner/src/registry.rs")).unwrap();
+        assert!(registry.contains("&problem_4::Day4,"));
+        assert!(scaffold(&crates, 4).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+use crate::input::Source;
+use solution::Puzzle;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Option<Self> {
+        samples.sort();
+        Some(Self {
+            min: *samples.first()?,
+            median: samples[samples.len() / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:?}, median {:?}, max {:?}",
+            self.min, self.median, self.max
+        )
+    }
+}
+
+/// Parses and solves each selected part `iterations` times, then prints
+/// min/median/max for parse, part 1 and part 2.
+pub fn bench(
+    puzzle: &dyn Puzzle,
+    part: Option<usize>,
+    source: &Source,
+    iterations: usize,
+) -> Result<(), String> {
+    let input = source.load(puzzle.day())?;
+    let mut parse = Vec::new();
+    let mut parts = [Vec::new(), Vec::new()];
+    for _ in 0..iterations {
+        let (parsed, elapsed) = time(|| puzzle.parse(&input));
+        let parsed = parsed.map_err(|e| format!("day {}: {}", puzzle.day(), e))?;
+        parse.push(elapsed);
+        for (i, samples) in parts.iter_mut().enumerate() {
+            let p = i + 1;
+            if part.is_none_or(|part| part == p) {
+                let (_, elapsed) = time(|| parsed.part(p));
+                samples.push(elapsed);
+            }
+        }
+    }
+    let [part1, part2] = &mut parts;
+    for (label, samples) in [("parse", &mut parse), ("part1", part1), ("part2", part2)] {
+        if let Some(stats) = Stats::from_samples(samples) {
+            println!("{}: {}", label, stats);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut samples = [5, 1, 3, 2, 4].map(Duration::from_millis);
+        let stats = Stats::from_samples(&mut samples).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        assert_eq!(Stats::from_samples(&mut []), None);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::input::Source;
+use solution::Puzzle;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Name of the expected-answers file, stored next to the inputs.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Expected answers per day, read from lines of `<day> <part_1> <part_2>`.
+/// `-` marks an answer that isn't known yet and `#` starts a comment.
+#[derive(Debug, Default)]
+pub struct Answers(HashMap<u32, [Option<String>; 2]>);
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut answers = HashMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [day, part_1, part_2] = fields[..] else {
+                return Err(format!(
+                    "line {}: expected `<day> <part_1> <part_2>`",
+                    i + 1
+                ));
+            };
+            let day = day
+                .parse::<u32>()
+                .map_err(|_| format!("line {}: invalid day `{}`", i + 1, day))?;
+            let known = |s: &str| (s != "-").then(|| s.to_string());
+            answers.insert(day, [known(part_1), known(part_2)]);
+        }
+        Ok(Self(answers))
+    }
+
+    /// Loads the answers file in `dir`, treating a missing file as empty.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(ANSWERS_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn expected(&self, day: u32, part: usize) -> Option<&str> {
+        self.0.get(&day)?.get(part - 1)?.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing { actual: String },
+    NoInput,
+    Invalid(String),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {}, got {})", expected, actual)
+            }
+            Status::Missing { actual } => write!(f, "missing (got {})", actual),
+            Status::NoInput => write!(f, "missing (no input)"),
+            Status::Invalid(e) => write!(f, "FAIL (invalid input: {})", e),
+        }
+    }
+}
+
+pub fn check(expected: Option<&str>, actual: String) -> Status {
+    match expected {
+        Some(expected) if expected == actual => Status::Pass,
+        Some(expected) => Status::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+        None => Status::Missing { actual },
+    }
+}
+
+/// Runs every given day against the inputs in `dir` and prints one status
+/// line per part. Returns whether every known answer matched.
+pub fn verify(puzzles: &[&dyn Puzzle], dir: &Path) -> Result<bool, String> {
+    let answers = Answers::load(dir)?;
+    let source = Source::Dir(dir.to_path_buf());
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for puzzle in puzzles {
+        let day = puzzle.day();
+        let statuses = match source.load(day) {
+            Ok(input) => match puzzle.parse(&input) {
+                Ok(parsed) => (1..=2)
+                    .map(|part| check(answers.expected(day, part), parsed.part(part).to_string()))
+                    .collect(),
+                Err(e) => vec![Status::Invalid(e.to_string()); 2],
+            },
+            Err(_) => vec![Status::NoInput, Status::NoInput],
+        };
+        for (i, status) in statuses.iter().enumerate() {
+            match status {
+                Status::Pass => passed += 1,
+                Status::Fail { .. } | Status::Invalid(_) => failed += 1,
+                Status::Missing { .. } | Status::NoInput => missing += 1,
+            }
+            println!("day {} part {}: {}", day, i + 1, status);
+        }
+    }
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    Ok(failed == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse("# comment\n1 11 31\n\n2 2 - # part 2 unknown\n").unwrap();
+        assert_eq!(answers.expected(1, 1), Some("11"));
+        assert_eq!(answers.expected(1, 2), Some("31"));
+        assert_eq!(answers.expected(2, 2), None);
+        assert_eq!(answers.expected(3, 1), None);
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        assert!(Answers::parse("1 2").is_err());
+        assert!(Answers::parse("one 2 3").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(Some("5"), "5".to_string()), Status::Pass);
+        assert_eq!(
+            check(Some("5"), "6".to_string()),
+            Status::Fail {
+                expected: "5".to_string(),
+                actual: "6".to_string()
+            }
+        );
+        assert_eq!(
+            check(None, "6".to_string()),
+            Status::Missing {
+                actual: "6".to_string()
+            }
+        );
+    }
+}
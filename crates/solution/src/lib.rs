@@ -0,0 +1,212 @@
+use std::fmt;
+
+/// The answer to one part of a day, normalized so every day can be
+/// reported and compared the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Unsolved,
+    Number(i128),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsolved => write!(f, "unsolved"),
+            Answer::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Number(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(i32, i64, u32, u64, usize);
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Answer::Unsolved, Into::into)
+    }
+}
+
+/// Malformed puzzle input, located by 1-based line and column where known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at_column(column: usize, message: impl Into<String>) -> Self {
+        Self {
+            column: Some(column),
+            ..Self::new(message)
+        }
+    }
+
+    /// Sets the line, unless a more precise one is already known.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Shifts the line number, for errors from a section that doesn't start
+    /// at the top of the input.
+    pub fn offset_lines(mut self, offset: usize) -> Self {
+        if let Some(line) = &mut self.line {
+            *line += offset;
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: ", line, column)?,
+            (Some(line), None) => write!(f, "line {}: ", line)?,
+            (None, Some(column)) => write!(f, "column {}: ", column)?,
+            (None, None) => {}
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A single day's puzzle: how to parse the input and how to solve each part.
+pub trait Solution {
+    type Input: 'static;
+
+    const DAY: u32;
+    const TITLE: &'static str;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part_1(input: &Self::Input) -> Answer;
+    fn part_2(input: &Self::Input) -> Answer;
+}
+
+/// Object-safe view of a [`Solution`], so days with different input types
+/// can live in the same registry.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u32;
+    fn title(&self) -> &'static str;
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError>;
+}
+
+/// A parsed input, ready to be solved.
+pub trait Parsed {
+    fn part_1(&self) -> Answer;
+    fn part_2(&self) -> Answer;
+
+    fn part(&self, part: usize) -> Answer {
+        match part {
+            1 => self.part_1(),
+            2 => self.part_2(),
+            _ => Answer::Unsolved,
+        }
+    }
+}
+
+struct Prepared<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for Prepared<S> {
+    fn part_1(&self) -> Answer {
+        S::part_1(&self.0)
+    }
+
+    fn part_2(&self) -> Answer {
+        S::part_2(&self.0)
+    }
+}
+
+impl<S: Solution + Sync + 'static> Puzzle for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+        let input = S::parse(input)?;
+        Ok(Box::new(Prepared::<S>(input)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Example;
+
+    impl Solution for Example {
+        type Input = Vec<i32>;
+
+        const DAY: u32 = 0;
+        const TITLE: &'static str = "Example";
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            input
+                .split(',')
+                .map(|n| {
+                    n.parse()
+                        .map_err(|_| ParseError::new(format!("expected a number, found `{}`", n)))
+                })
+                .collect()
+        }
+
+        fn part_1(input: &Self::Input) -> Answer {
+            input.iter().sum::<i32>().into()
+        }
+
+        fn part_2(_input: &Self::Input) -> Answer {
+            None::<i32>.into()
+        }
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(42u64).to_string(), "42");
+        assert_eq!(Answer::from(Some(-7)).to_string(), "-7");
+        assert_eq!(Answer::from(None::<usize>).to_string(), "unsolved");
+    }
+
+    #[test]
+    fn test_puzzle() {
+        let puzzle: &dyn Puzzle = &Example;
+        assert_eq!(puzzle.day(), 0);
+        assert_eq!(puzzle.title(), "Example");
+        let parsed = puzzle.parse("1,2,3").unwrap();
+        assert_eq!(parsed.part_1(), Answer::Number(6));
+        assert_eq!(parsed.part(2), Answer::Unsolved);
+        let err = puzzle.parse("1,x").err().unwrap();
+        assert_eq!(err.to_string(), "expected a number, found `x`");
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let err = ParseError::new("expected `N|M`").on_line(37);
+        assert_eq!(err.to_string(), "line 37: expected `N|M`");
+        assert_eq!(err.offset_lines(3).line, Some(40));
+        assert_eq!(ParseError::new("empty").to_string(), "empty");
+    }
+}